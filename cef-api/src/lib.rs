@@ -35,6 +35,8 @@ pub struct InternalApi {
     cef_browser_ready: unsafe extern "C" fn(browser: u32) -> bool,
     cef_on_browser_ready: unsafe extern "C" fn(browser: u32, callback: BrowserReadyCallback),
     cef_gta_window_active: unsafe extern "C" fn() -> bool,
+    cef_set_browser_max_fps: unsafe extern "C" fn(browser: u32, max_fps: i32),
+    cef_set_low_power_mode: unsafe extern "C" fn(enabled: bool),
 }
 
 pub struct CefApi;
@@ -133,4 +135,17 @@ impl CefApi {
     pub fn is_window_active() -> bool {
         unsafe { ((*API).cef_gta_window_active)() }
     }
+
+    /// `0` removes the limit.
+    pub fn set_browser_max_fps(browser: u32, max_fps: i32) {
+        unsafe {
+            ((*API).cef_set_browser_max_fps)(browser, max_fps);
+        }
+    }
+
+    pub fn set_low_power_mode(enabled: bool) {
+        unsafe {
+            ((*API).cef_set_low_power_mode)(enabled);
+        }
+    }
 }
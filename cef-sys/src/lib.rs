@@ -7,3 +7,8 @@
 )]
 
 include!("bindings.rs");
+
+// cef_version.h isn't part of the generated bindings.
+unsafe extern "C" {
+    pub fn cef_version_info(entry: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
+}
@@ -76,6 +76,48 @@ impl Browser {
 
         unsafe { is_loading(self.inner.get_mut()) == 1 }
     }
+
+    pub fn can_go_back(&self) -> bool {
+        let can_go_back = self.inner.can_go_back.unwrap();
+
+        unsafe { can_go_back(self.inner.get_mut()) == 1 }
+    }
+
+    pub fn go_back(&self) {
+        let go_back = self.inner.go_back.unwrap();
+
+        unsafe { go_back(self.inner.get_mut()) }
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        let can_go_forward = self.inner.can_go_forward.unwrap();
+
+        unsafe { can_go_forward(self.inner.get_mut()) == 1 }
+    }
+
+    pub fn go_forward(&self) {
+        let go_forward = self.inner.go_forward.unwrap();
+
+        unsafe { go_forward(self.inner.get_mut()) }
+    }
+
+    pub fn reload(&self) {
+        let reload = self.inner.reload.unwrap();
+
+        unsafe { reload(self.inner.get_mut()) }
+    }
+
+    pub fn reload_ignore_cache(&self) {
+        let reload_ignore_cache = self.inner.reload_ignore_cache.unwrap();
+
+        unsafe { reload_ignore_cache(self.inner.get_mut()) }
+    }
+
+    pub fn stop_load(&self) {
+        let stop_load = self.inner.stop_load.unwrap();
+
+        unsafe { stop_load(self.inner.get_mut()) }
+    }
 }
 
 impl Clone for Browser {
@@ -232,6 +274,28 @@ impl BrowserHost {
 
         unsafe { get(self.inner.get_mut()) }
     }
+
+    pub fn set_zoom_level(&self, level: f64) {
+        let set = self.inner.set_zoom_level.unwrap();
+
+        unsafe {
+            set(self.inner.get_mut(), level);
+        }
+    }
+
+    pub fn zoom_level(&self) -> f64 {
+        let get = self.inner.get_zoom_level.unwrap();
+
+        unsafe { get(self.inner.get_mut()) }
+    }
+
+    pub fn notify_screen_info_changed(&self) {
+        let notify = self.inner.notify_screen_info_changed.unwrap();
+
+        unsafe {
+            notify(self.inner.get_mut());
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -272,6 +336,13 @@ impl Frame {
         V8Context::from_raw(ctx)
     }
 
+    pub fn url(&self) -> CefString {
+        let get_url = self.inner.get_url.unwrap();
+        let url = unsafe { get_url(self.inner.get_mut()) };
+
+        CefString::from(url)
+    }
+
     pub fn load_url(&self, url: &str) {
         let url = CefString::new(url);
         let load = self.inner.load_url.unwrap();
@@ -281,6 +352,21 @@ impl Frame {
         }
     }
 
+    pub fn execute_java_script(&self, code: &str, script_url: &str, start_line: i32) {
+        let code = CefString::new(code);
+        let script_url = CefString::new(script_url);
+        let execute = self.inner.execute_java_script.unwrap();
+
+        unsafe {
+            execute(
+                self.inner.get_mut(),
+                code.as_cef_string(),
+                script_url.as_cef_string(),
+                start_line,
+            );
+        }
+    }
+
     pub fn send_process_message(&self, target_process: ProcessId, message: ProcessMessage) {
         let send = self
             .inner
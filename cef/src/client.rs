@@ -3,6 +3,7 @@ use crate::handlers::context_menu::ContextMenuHandler;
 use crate::handlers::lifespan::LifespanHandler;
 use crate::handlers::load::LoadHandler;
 use crate::handlers::render::RenderHandler;
+use crate::handlers::request::RequestHandler;
 
 use crate::ProcessId;
 use crate::browser::{Browser, Frame};
@@ -14,6 +15,7 @@ pub trait Client {
     type ContextMenuHandler: ContextMenuHandler;
     type LoadHandler: LoadHandler;
     type AudioHandler: AudioHandler;
+    type RequestHandler: RequestHandler;
 
     fn lifespan_handler(&self) -> Option<Self::LifespanHandler> {
         None
@@ -35,6 +37,10 @@ pub trait Client {
         None
     }
 
+    fn request_handler(&self) -> Option<Self::RequestHandler> {
+        None
+    }
+
     fn on_process_message(
         &self, _browser: Browser, _frame: Frame, _source: ProcessId, _message: ProcessMessage,
     ) -> bool {
@@ -5,4 +5,5 @@ pub mod lifespan;
 pub mod load;
 pub mod render;
 pub mod render_process;
+pub mod request;
 pub mod v8handler;
@@ -1,8 +1,15 @@
 use crate::browser::{Browser, Frame};
+use crate::types::string::CefString;
 
 pub trait LoadHandler {
     fn on_load_end(&self, _browser: Browser, _frame: Frame, _status_code: i32) {}
 
+    fn on_load_error(
+        &self, _browser: Browser, _frame: Frame, _error_code: i32, _error_text: CefString,
+        _failed_url: CefString,
+    ) {
+    }
+
     fn on_loading_state_change(
         &self, _browser: Browser, _is_loading: bool, _can_go_back: bool, _can_go_forward: bool,
     ) {
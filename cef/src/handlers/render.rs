@@ -1,5 +1,5 @@
 use crate::browser::Browser;
-use cef_sys::{cef_paint_element_type_t, cef_rect_t};
+use cef_sys::{cef_paint_element_type_t, cef_rect_t, cef_screen_info_t};
 
 #[derive(Debug)]
 pub struct DirtyRects {
@@ -39,6 +39,12 @@ impl From<PaintElement> for cef_paint_element_type_t::Type {
 
 pub trait RenderHandler {
     fn view_rect(&self, browser: Browser, rect: &mut cef_rect_t);
+
+    /// `false` - использовать значения по умолчанию
+    fn screen_info(&self, _browser: Browser, _info: &mut cef_screen_info_t) -> bool {
+        false
+    }
+
     fn on_popup_show(&self, browser: Browser, show: bool);
     fn on_popup_size(&self, browser: Browser, rect: &cef_rect_t);
     fn on_paint(
@@ -0,0 +1,10 @@
+use crate::browser::Browser;
+
+use cef_sys::cef_termination_status_t;
+
+pub trait RequestHandler {
+    fn on_render_process_terminated(
+        &self, _browser: Browser, _status: cef_termination_status_t::Type,
+    ) {
+    }
+}
@@ -63,6 +63,20 @@ pub fn shutdown() {
     }
 }
 
+/// CEF version of the loaded libcef, e.g. `3.4044.1851`.
+pub fn cef_version() -> String {
+    let [major, minor, patch] = [0, 1, 2].map(|entry| unsafe { cef_sys::cef_version_info(entry) });
+    format!("{}.{}.{}", major, minor, patch)
+}
+
+/// Chromium version of the loaded libcef, e.g. `83.0.4103.106`.
+pub fn chromium_version() -> String {
+    let [major, minor, build, patch] =
+        [4, 5, 6, 7].map(|entry| unsafe { cef_sys::cef_version_info(entry) });
+
+    format!("{}.{}.{}.{}", major, minor, build, patch)
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum ProcessId {
     None,
@@ -12,6 +12,7 @@ pub mod lifespan_handler;
 pub mod load_handler;
 pub mod render_handler;
 pub mod render_process_handler;
+pub mod request_handler;
 pub mod task;
 pub mod v8handler;
 
@@ -106,6 +107,7 @@ mod tests {
     use crate::handlers::load::LoadHandler;
     use crate::handlers::render::{DirtyRects, PaintElement, RenderHandler};
     use crate::handlers::render_process::RenderProcessHandler;
+    use crate::handlers::request::RequestHandler;
     use crate::handlers::v8handler::V8Handler;
     use crate::task::Task;
     use crate::types::string::CefString;
@@ -113,7 +115,8 @@ mod tests {
     use cef_sys::{
         cef_app_t, cef_audio_handler_t, cef_browser_process_handler_t, cef_client_t,
         cef_context_menu_handler_t, cef_life_span_handler_t, cef_load_handler_t, cef_rect_t,
-        cef_render_handler_t, cef_render_process_handler_t, cef_task_t, cef_v8handler_t,
+        cef_render_handler_t, cef_render_process_handler_t, cef_request_handler_t, cef_task_t,
+        cef_v8handler_t,
     };
 
     struct DropTracker {
@@ -206,6 +209,9 @@ mod tests {
     struct DummyAudioHandler;
     impl AudioHandler for DummyAudioHandler {}
 
+    struct DummyRequestHandler;
+    impl RequestHandler for DummyRequestHandler {}
+
     struct DummyClient;
     impl Client for DummyClient {
         type LifespanHandler = DummyLifespanHandler;
@@ -213,6 +219,7 @@ mod tests {
         type ContextMenuHandler = DummyContextMenuHandler;
         type LoadHandler = DummyLoadHandler;
         type AudioHandler = DummyAudioHandler;
+        type RequestHandler = DummyRequestHandler;
     }
 
     struct DummyTask;
@@ -366,6 +373,7 @@ mod tests {
                 read_cef_object::<cef_load_handler_t, DummyLoadHandler>(ptr);
             assert!(cef.on_loading_state_change.is_some());
             assert!(cef.on_load_end.is_some());
+            assert!(cef.on_load_error.is_some());
             drop_wrapper::<cef_load_handler_t, DummyLoadHandler>(ptr);
         }
     }
@@ -385,6 +393,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn wrap_request_handler_sets_callbacks() {
+        let ptr = super::request_handler::wrap(DummyRequestHandler);
+        unsafe {
+            let cef: cef_request_handler_t =
+                read_cef_object::<cef_request_handler_t, DummyRequestHandler>(ptr);
+            assert!(cef.on_render_process_terminated.is_some());
+            drop_wrapper::<cef_request_handler_t, DummyRequestHandler>(ptr);
+        }
+    }
+
     #[test]
     fn wrap_client_sets_callbacks() {
         let ptr = super::client::wrap(DummyClient);
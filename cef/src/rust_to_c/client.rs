@@ -123,9 +123,14 @@ extern "system" fn render<I: Client>(this: *mut cef_client_t) -> *mut cef_render
 
 // request
 
-extern "system" fn request(_this: *mut cef_client_t) -> *mut cef_request_handler_t {
-    println!("request");
-    null_mut()
+extern "system" fn request<I: Client>(this: *mut cef_client_t) -> *mut cef_request_handler_t {
+    let obj: &mut Wrapper<_, I> = Wrapper::unwrap(this);
+
+    if let Some(handler) = obj.interface.request_handler() {
+        super::request_handler::wrap(handler)
+    } else {
+        null_mut()
+    }
 }
 
 // message received
@@ -165,7 +170,7 @@ pub fn wrap<T: Client>(client: T) -> *mut cef_client_t {
 
     object.get_life_span_handler = Some(lifespan::<T>);
     object.on_process_message_received = Some(on_process_message::<T>);
-    object.get_request_handler = Some(request);
+    object.get_request_handler = Some(request::<T>);
     object.get_render_handler = Some(render::<T>);
     object.get_load_handler = Some(load::<T>);
     object.get_keyboard_handler = Some(keyboard);
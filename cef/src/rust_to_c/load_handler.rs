@@ -1,8 +1,9 @@
 use crate::browser::{Browser, Frame};
 use crate::handlers::load::LoadHandler;
 use crate::rust_to_c::Wrapper;
+use crate::types::string::CefString;
 
-use cef_sys::{cef_browser_t, cef_frame_t, cef_load_handler_t};
+use cef_sys::{cef_browser_t, cef_errorcode_t, cef_frame_t, cef_load_handler_t, cef_string_t};
 
 use std::os::raw::c_int;
 
@@ -33,11 +34,28 @@ unsafe extern "system" fn on_load_end<I: LoadHandler>(
     obj.interface.on_load_end(browser, frame, status_code);
 }
 
+unsafe extern "system" fn on_load_error<I: LoadHandler>(
+    this: *mut cef_load_handler_t, browser: *mut cef_browser_t, frame: *mut cef_frame_t,
+    error_code: cef_errorcode_t::Type, error_text: *const cef_string_t,
+    failed_url: *const cef_string_t,
+) {
+    let obj: &mut Wrapper<_, I> = Wrapper::unwrap(this);
+
+    let browser = Browser::from_raw_add_ref(browser);
+    let frame = Frame::from_raw_add_ref(frame);
+    let error_text = CefString::from(error_text);
+    let failed_url = CefString::from(failed_url);
+
+    obj.interface
+        .on_load_error(browser, frame, error_code, error_text, failed_url);
+}
+
 pub fn wrap<T: LoadHandler>(load: T) -> *mut cef_load_handler_t {
     let mut object: cef_load_handler_t = unsafe { std::mem::zeroed() };
 
     object.on_loading_state_change = Some(on_loading_state_change::<T>);
     object.on_load_end = Some(on_load_end::<T>);
+    object.on_load_error = Some(on_load_error::<T>);
 
     let wrapper = Wrapper::new(object, load);
 
@@ -46,11 +46,21 @@ unsafe extern "system" fn get_screen_point(
     0
 }
 
-unsafe extern "system" fn get_screen_info(
-    _this: *mut cef_render_handler_t, _browser: *mut cef_browser_t,
-    _screen_info: *mut cef_screen_info_t,
+unsafe extern "system" fn get_screen_info<I: RenderHandler>(
+    this: *mut cef_render_handler_t, browser: *mut cef_browser_t,
+    screen_info: *mut cef_screen_info_t,
 ) -> ::std::os::raw::c_int {
-    0
+    unsafe {
+        if browser.is_null() || screen_info.is_null() {
+            return 0;
+        }
+
+        let obj: &mut Wrapper<_, I> = Wrapper::unwrap(this);
+        let browser = Browser::from_raw_borrowed(browser);
+        let screen_info = &mut *screen_info;
+
+        obj.interface.screen_info(browser, screen_info) as _
+    }
 }
 
 unsafe extern "system" fn on_popup_show<I: RenderHandler>(
@@ -185,7 +195,7 @@ pub fn wrap<T: RenderHandler>(object: T) -> *mut cef_render_handler_t {
     cef_object.get_root_screen_rect = Some(get_root_screen_rect::<T>);
     cef_object.get_view_rect = Some(get_view_rect::<T>);
     cef_object.get_screen_point = Some(get_screen_point);
-    cef_object.get_screen_info = Some(get_screen_info);
+    cef_object.get_screen_info = Some(get_screen_info::<T>);
     cef_object.on_popup_show = Some(on_popup_show::<T>);
     cef_object.on_popup_size = Some(on_popup_size::<T>);
     cef_object.on_paint = Some(on_paint::<T>);
@@ -0,0 +1,25 @@
+use crate::browser::Browser;
+use crate::handlers::request::RequestHandler;
+use crate::rust_to_c::Wrapper;
+
+use cef_sys::{cef_browser_t, cef_request_handler_t, cef_termination_status_t};
+
+unsafe extern "system" fn on_render_process_terminated<I: RequestHandler>(
+    this: *mut cef_request_handler_t, browser: *mut cef_browser_t,
+    status: cef_termination_status_t::Type,
+) {
+    let obj: &mut Wrapper<_, I> = Wrapper::unwrap(this);
+    let browser = Browser::from_raw_add_ref(browser);
+
+    obj.interface.on_render_process_terminated(browser, status);
+}
+
+pub fn wrap<T: RequestHandler>(request: T) -> *mut cef_request_handler_t {
+    let mut object: cef_request_handler_t = unsafe { std::mem::zeroed() };
+
+    object.on_render_process_terminated = Some(on_render_process_terminated::<T>);
+
+    let wrapper = Wrapper::new(object, request);
+
+    Box::into_raw(Box::new(wrapper)) as *mut _
+}
@@ -1,7 +1,7 @@
 use crate::handlers::v8handler::V8Handler;
 use crate::ref_counted::RefGuard;
 use crate::types::string::CefString;
-use cef_sys::{cef_v8context_t, cef_v8value_t};
+use cef_sys::{cef_v8context_t, cef_v8exception_t, cef_v8value_t};
 
 #[derive(Clone)]
 pub struct V8Context {
@@ -68,6 +68,45 @@ impl V8Context {
         unsafe { is_same(self.inner.get_mut(), another.clone().inner.into_cef()) == 1 }
     }
 
+    /// Returns the exception message as an error.
+    pub fn eval(&self, code: &str) -> Result<V8Value, CefString> {
+        let eval = self.inner.eval.unwrap();
+        let code = CefString::new(code);
+        let script_url = CefString::new_empty();
+
+        let mut retval: *mut cef_v8value_t = std::ptr::null_mut();
+        let mut exception: *mut cef_v8exception_t = std::ptr::null_mut();
+
+        let ok = unsafe {
+            eval(
+                self.inner.get_mut(),
+                code.as_cef_string(),
+                script_url.as_cef_string(),
+                0,
+                &mut retval,
+                &mut exception,
+            ) == 1
+        };
+
+        if ok && !retval.is_null() {
+            return Ok(V8Value::from_raw(retval));
+        }
+
+        if exception.is_null() {
+            return Err(CefString::new_empty());
+        }
+
+        let exception = RefGuard::from_raw(exception);
+        let message = exception
+            .get_message
+            .map(|get| unsafe { get(exception.get_mut()) })
+            .filter(|ptr| !ptr.is_null())
+            .map(CefString::from)
+            .unwrap_or_else(CefString::new_empty);
+
+        Err(message)
+    }
+
     pub fn with_in<F: Fn()>(&self, function: F) {
         self.enter();
 
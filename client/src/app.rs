@@ -7,11 +7,15 @@ use parking_lot::Mutex;
 use cef::types::list::List;
 use cef_sys::{cef_key_event_t, cef_key_event_type_t};
 
+use messages::packets::{EventValue, NavigationAction};
+
 use winapi::shared::minwindef::{LPARAM, UINT, WPARAM};
 use winapi::um::winuser::*;
 
 use crate::audio::Audio;
 use crate::browser::manager::{Manager, MouseKey};
+use crate::browser::screenshot::{self, Frame, ScreenshotError};
+use crate::browser::view::BrowserRect;
 use crate::external::CallbackList;
 use crate::network::NetworkClient;
 use crate::static_cell::StaticCell;
@@ -23,6 +27,7 @@ use client_api::samp::netgame::NetGame;
 use client_api::samp::objects::Object;
 use client_api::samp::players::local_player;
 
+use client_api::utils::handle_result;
 use client_api::wndproc;
 
 use crossbeam_channel::{Receiver, Sender};
@@ -30,7 +35,7 @@ use crossbeam_channel::{Receiver, Sender};
 use retour::GenericDetour;
 
 const CEF_SERVER_PORT_OFFSET: u16 = 2;
-pub const CEF_PLUGIN_VERSION: i32 = 0x00_01_00;
+pub const CEF_PLUGIN_VERSION: i32 = 0x00_01_03;
 const CONNECT_BACKOFF_BASE: Duration = Duration::from_secs(1);
 const CONNECT_BACKOFF_MAX: Duration = Duration::from_secs(10);
 
@@ -55,18 +60,41 @@ pub enum Event {
     DestroyBrowser(u32),
     HideBrowser(u32, bool),
     FocusBrowser(u32, bool),
-    EmitEvent(String, List),
-    EmitEventOnServer(String, String),
+    EmitEvent(String, List, Option<u32>),
+    EmitEventOnServer(u32, String, String, Vec<EventValue<'static>>),
     BrowserCreated(u32, i32),
+    BrowserLoaded(u32, String, i32),
+    BrowserLoadError(u32, i32, String, String),
+    BrowserDestroyed(u32),
+    RendererCrashed(u32, i32),
     AppendToObject(u32, i32),
     RemoveFromObject(u32, i32),
     ToggleDevTools(u32, bool),
     SetAudioSettings(u32, crate::audio::BrowserAudioSettings),
     LoadUrl(u32, String),
+    SetBrowserRect(u32, Option<BrowserRect>),
+    SetBrowserLayer(u32, i32),
+    SetZoom(u32, f64, Option<f32>),
+    SetBrowserAudio(u32, Option<f32>, Option<bool>),
+    SetBrowserMaxFps(u32, i32),
+    SetLowPowerMode(bool),
+    NavigateBrowser(u32, NavigationAction),
+    LoadingStateChanged(u32, bool, bool, bool),
+    ExecuteJavaScript(u32, String, Option<u32>),
+    JavaScriptResult(u32, u32, bool, String),
+    /// без request_id скриншот сохраняется у игрока
+    TakeScreenshot(u32, Option<u32>),
+    ScreenshotTaken(u32, u32, Option<Vec<u8>>),
+    /// файлы сервера, которых нет в кэше
+    RequestAssets(Vec<String>),
+    /// фокус или видимость поменяли js или плагин, сервер об этом не знает
+    BrowserFocusChanged(u32, bool),
+    BrowserVisibilityChanged(u32, bool),
 
     CefInitialize,
 
     AlwaysListenKeys(u32, bool),
+    ClientInfo(ClientInfo),
     Terminate,
 }
 
@@ -78,6 +106,17 @@ pub struct ExternalBrowser {
     pub scale: i32,
 }
 
+#[derive(Debug)]
+pub struct ClientInfo {
+    pub width: u32,
+    pub height: u32,
+    pub cef_version: String,
+    pub chromium_version: String,
+    pub locale: String,
+    pub render_mode: crate::utils::RenderMode,
+    pub plugins: Vec<String>,
+}
+
 pub struct App {
     connected: bool,
     window_focused: bool,
@@ -86,6 +125,7 @@ pub struct App {
     bad_version_notified: bool,
     connect_backoff: Duration,
     next_connect_attempt: Instant,
+    resolution: [usize; 2],
 
     manager: Arc<Mutex<Manager>>,
     audio: Arc<Audio>,
@@ -163,6 +203,7 @@ impl App {
             bad_version_notified: false,
             connect_backoff: CONNECT_BACKOFF_BASE,
             next_connect_attempt: Instant::now(),
+            resolution: [0, 0],
             network: None,
             initialization: Instant::now(),
             manager,
@@ -284,6 +325,32 @@ impl App {
         self.connect_backoff = std::cmp::min(doubled, CONNECT_BACKOFF_MAX);
     }
 
+    /// отправляет серверу информацию о клиенте, если разрешение изменилось
+    /// или `force` (сразу после входа)
+    fn send_client_info(&mut self, force: bool) {
+        let resolution = crate::utils::client_rect();
+
+        if !force && resolution == self.resolution {
+            return;
+        }
+
+        self.resolution = resolution;
+
+        if let Some(network) = self.network.as_mut() {
+            let info = ClientInfo {
+                width: resolution[0] as u32,
+                height: resolution[1] as u32,
+                cef_version: cef::cef_version(),
+                chromium_version: cef::chromium_version(),
+                locale: crate::utils::user_locale(),
+                render_mode: crate::utils::current_render_mode(),
+                plugins: crate::external::plugin_names(),
+            };
+
+            network.send(Event::ClientInfo(info));
+        }
+    }
+
     pub fn manager(&self) -> Arc<Mutex<Manager>> {
         self.manager.clone()
     }
@@ -446,14 +513,18 @@ pub fn mainloop() {
                     client_api::samp::inputs::show_cursor(show_cursor);
                 }
 
-                Event::EmitEvent(event, list) => {
+                Event::EmitEvent(event, list, browser_id) => {
                     let manager = app.manager.lock();
-                    manager.trigger_event(&event, list);
+
+                    match browser_id {
+                        Some(id) => manager.trigger_browser_event(id, &event, list),
+                        None => manager.trigger_event(&event, list),
+                    }
                 }
 
-                Event::EmitEventOnServer(event, arguments) => {
+                Event::EmitEventOnServer(id, event, arguments, values) => {
                     if let Some(network) = app.network.as_mut() {
-                        let event = Event::EmitEventOnServer(event, arguments);
+                        let event = Event::EmitEventOnServer(id, event, arguments, values);
                         network.send(event);
                     }
                 }
@@ -474,6 +545,18 @@ pub fn mainloop() {
                     crate::external::browser_created(id, code);
                 }
 
+                event @ (Event::BrowserLoaded(..)
+                | Event::BrowserLoadError(..)
+                | Event::BrowserDestroyed(_)
+                | Event::RendererCrashed(..)
+                | Event::LoadingStateChanged(..)
+                | Event::BrowserFocusChanged(..)
+                | Event::BrowserVisibilityChanged(..)) => {
+                    if let Some(network) = app.network.as_mut() {
+                        network.send(event);
+                    }
+                }
+
                 Event::CefInitialize => {
                     log::trace!("Initialized. Elapsed: {:?}", app.initialization.elapsed());
 
@@ -500,6 +583,7 @@ pub fn mainloop() {
                     app.connected = true;
                     app.bad_version_notified = false;
                     app.reset_connect_backoff();
+                    app.send_client_info(true);
                     crate::external::call_connect();
                 }
 
@@ -541,12 +625,82 @@ pub fn mainloop() {
                     manager.load_url(browser, &url);
                 }
 
+                Event::SetBrowserRect(browser, rect) => {
+                    let manager = app.manager.lock();
+                    manager.set_browser_rect(browser, rect);
+                }
+
+                Event::SetBrowserLayer(browser, layer) => {
+                    let mut manager = app.manager.lock();
+                    manager.set_browser_layer(browser, layer);
+                }
+
+                Event::SetZoom(browser, level, scale) => {
+                    let manager = app.manager.lock();
+                    manager.set_zoom(browser, level, scale);
+                }
+
+                Event::SetBrowserAudio(browser, volume, muted) => {
+                    let manager = app.manager.lock();
+                    manager.set_browser_volume(browser, volume, muted);
+                }
+
+                Event::SetBrowserMaxFps(browser, max_fps) => {
+                    let manager = app.manager.lock();
+                    manager.set_browser_max_fps(browser, max_fps);
+                }
+
+                Event::SetLowPowerMode(enabled) => {
+                    let mut manager = app.manager.lock();
+                    manager.set_low_power_mode(enabled);
+                }
+
+                Event::NavigateBrowser(browser, action) => {
+                    let manager = app.manager.lock();
+                    manager.navigate(browser, action);
+                }
+
+                Event::ExecuteJavaScript(browser, code, request_id) => {
+                    let manager = app.manager.lock();
+                    manager.execute_javascript(browser, &code, request_id);
+                }
+
+                Event::JavaScriptResult(browser, request_id, success, result) => {
+                    if let Some(network) = app.network.as_mut() {
+                        let event = Event::JavaScriptResult(browser, request_id, success, result);
+                        network.send(event);
+                    }
+                }
+
+                Event::TakeScreenshot(browser, request_id) => {
+                    let frame = app.manager.lock().browser_frame(browser);
+                    let event_tx = app.event_tx.clone();
+
+                    // кодирование в png не должно тормозить игру
+                    std::thread::spawn(move || {
+                        take_screenshot(event_tx, browser, request_id, frame)
+                    });
+                }
+
+                Event::ScreenshotTaken(browser, request_id, png) => {
+                    if let Some(network) = app.network.as_mut() {
+                        network.send(Event::ScreenshotTaken(browser, request_id, png));
+                    }
+                }
+
+                Event::RequestAssets(paths) => {
+                    if let Some(network) = app.network.as_mut() {
+                        network.send(Event::RequestAssets(paths));
+                    }
+                }
+
                 _ => (),
             }
         }
 
         if app.cef_ready && app.connected {
             crate::external::call_mainloop();
+            app.send_client_info(false);
 
             if let Some(local) = local_player() {
                 let position = local.position();
@@ -589,6 +743,42 @@ pub fn mainloop() {
     }
 }
 
+fn take_screenshot(
+    event_tx: Sender<Event>, browser: u32, request_id: Option<u32>, frame: Option<Frame>,
+) {
+    let png = match frame {
+        Some(frame) => frame.encode_png(),
+        None => Err(ScreenshotError::Empty),
+    };
+
+    match (png, request_id) {
+        (Ok(png), None) => {
+            match screenshot::save(&crate::utils::screenshots_dir(), browser, &png) {
+                Ok(path) => log::info!(
+                    "Screenshot of browser {} saved to {}",
+                    browser,
+                    path.display()
+                ),
+                Err(err) => {
+                    log::error!("Couldn't save a screenshot of browser {}: {}", browser, err)
+                }
+            }
+        }
+
+        (Ok(png), Some(request_id)) => {
+            handle_result(event_tx.send(Event::ScreenshotTaken(browser, request_id, Some(png))));
+        }
+
+        (Err(err), request_id) => {
+            log::error!("Couldn't take a screenshot of browser {}: {}", browser, err);
+
+            if let Some(request_id) = request_id {
+                handle_result(event_tx.send(Event::ScreenshotTaken(browser, request_id, None)));
+            }
+        }
+    }
+}
+
 // TODO: Save key state. Mouse too?
 fn win_event(msg: UINT, wparam: WPARAM, lparam: LPARAM) -> bool {
     if let Some(app) = App::get() {
@@ -0,0 +1,492 @@
+use messages::packets;
+use sha2::{Digest, Sha256};
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::browser::assets_scheme::sanitize_asset_path;
+
+const MAX_ASSET_FILE_SIZE: u64 = 32 * 1024 * 1024;
+const MAX_ASSETS_SIZE: u64 = 256 * 1024 * 1024;
+const MAX_ASSET_FILES: usize = 4096;
+
+/// сколько ждать следующего куска, прежде чем считать скачивание неудачным
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// файл из манифеста сервера
+#[derive(Debug, Clone)]
+pub struct AssetFile {
+    pub name: String,
+    /// уже проверенный путь внутри папки кэша
+    pub path: PathBuf,
+    pub size: u64,
+    pub hash: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub enum ManifestError {
+    TooManyFiles(usize),
+    TooLarge(String, u64),
+    TotalSize(u64),
+    BadPath(String),
+    BadHash(String),
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::TooManyFiles(count) => write!(f, "too many files: {}", count),
+            ManifestError::TooLarge(name, size) => write!(f, "{} is too large: {}", name, size),
+            ManifestError::TotalSize(size) => write!(f, "assets are too large: {}", size),
+            ManifestError::BadPath(name) => write!(f, "bad path: {}", name),
+            ManifestError::BadHash(name) => write!(f, "bad hash of {}", name),
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+#[derive(Default)]
+struct Download {
+    total: u32,
+    size: u64,
+    chunks: BTreeMap<u32, Vec<u8>>,
+}
+
+/// скачивание файлов сервера в его папку кэша
+pub struct AssetCache {
+    root: PathBuf,
+    files: HashMap<String, AssetFile>,
+    downloads: HashMap<String, Download>,
+    failed: bool,
+    /// когда пришел последний кусок или был запрос
+    updated: Instant,
+}
+
+/// у каждого сервера своя папка, чтобы файлы разных серверов не смешивались
+pub fn cache_dir(address: SocketAddr) -> PathBuf {
+    let name = address.to_string().replace([':', '[', ']'], "_");
+    crate::utils::documents_path().join("servers").join(name)
+}
+
+impl AssetCache {
+    pub fn new(
+        root: PathBuf, manifest: &packets::AssetManifest,
+    ) -> Result<AssetCache, ManifestError> {
+        if manifest.files.len() > MAX_ASSET_FILES {
+            return Err(ManifestError::TooManyFiles(manifest.files.len()));
+        }
+
+        let mut files = HashMap::new();
+        let mut total_size = 0u64;
+
+        for file in &manifest.files {
+            let name = file.path.to_string();
+
+            if file.size > MAX_ASSET_FILE_SIZE {
+                return Err(ManifestError::TooLarge(name, file.size));
+            }
+
+            if file.hash.len() != 32 {
+                return Err(ManifestError::BadHash(name));
+            }
+
+            let Some(path) = sanitize_asset_path(&root, &name) else {
+                return Err(ManifestError::BadPath(name));
+            };
+
+            total_size += file.size;
+
+            let file = AssetFile {
+                name: name.clone(),
+                path,
+                size: file.size,
+                hash: file.hash.to_vec(),
+            };
+
+            files.insert(name, file);
+        }
+
+        if total_size > MAX_ASSETS_SIZE {
+            return Err(ManifestError::TotalSize(total_size));
+        }
+
+        Ok(AssetCache {
+            root,
+            files,
+            downloads: HashMap::new(),
+            failed: false,
+            updated: Instant::now(),
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn files(&self) -> Vec<AssetFile> {
+        self.files.values().cloned().collect()
+    }
+
+    /// ждем куски только для файлов из манифеста
+    pub fn request(&mut self, names: &[String]) {
+        for name in names {
+            if self.files.contains_key(name) {
+                self.downloads.insert(name.clone(), Download::default());
+            }
+        }
+
+        self.updated = Instant::now();
+    }
+
+    pub fn pending(&self) -> impl Iterator<Item = &str> {
+        self.downloads.keys().map(String::as_str)
+    }
+
+    /// возвращает `true`, если скачивание файла закончилось (успешно или нет)
+    pub fn push_chunk(&mut self, chunk: &packets::AssetChunk) -> bool {
+        let name = chunk.path.as_ref();
+
+        let (Some(file), Some(download)) = (self.files.get(name), self.downloads.get_mut(name))
+        else {
+            return false;
+        };
+
+        self.updated = Instant::now();
+
+        // сервер не может отдать файл
+        if chunk.total == 0 {
+            self.fail(name, "not available on the server");
+            return true;
+        }
+
+        if download.total == 0 {
+            download.total = chunk.total;
+        }
+
+        download.size += chunk.data.len() as u64;
+
+        // пустыми могут быть только куски пустого файла
+        let valid = chunk.total == download.total
+            && chunk.total as u64 <= file.size.max(1)
+            && chunk.index < chunk.total
+            && download.size <= file.size
+            && download
+                .chunks
+                .insert(chunk.index, chunk.data.to_vec())
+                .is_none();
+
+        if !valid {
+            self.fail(name, "broken chunk");
+            return true;
+        }
+
+        if download.chunks.len() < download.total as usize {
+            return false;
+        }
+
+        let Some(download) = self.downloads.remove(name) else {
+            return false;
+        };
+
+        let bytes: Vec<u8> = download.chunks.into_values().flatten().collect();
+
+        if !matches(file, &bytes) {
+            self.fail(name, "hash mismatch");
+            return true;
+        }
+
+        let result = file
+            .path
+            .parent()
+            .map(std::fs::create_dir_all)
+            .unwrap_or(Ok(()))
+            .and_then(|_| std::fs::write(&file.path, &bytes));
+
+        if let Err(err) = result {
+            self.fail(name, &err.to_string());
+        }
+
+        true
+    }
+
+    fn fail(&mut self, name: &str, reason: &str) {
+        log::error!("CEF Assets: couldn't download {}: {}", name, reason);
+
+        self.downloads.remove(name);
+        self.failed = true;
+    }
+
+    /// сервер перестал присылать куски, недокачанные файлы считаются неудачными
+    pub fn check_timeout(&mut self) -> bool {
+        if self.downloads.is_empty() || self.updated.elapsed() < DOWNLOAD_TIMEOUT {
+            return false;
+        }
+
+        let names: Vec<String> = self.downloads.keys().cloned().collect();
+        names.iter().for_each(|name| self.fail(name, "timed out"));
+
+        true
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.downloads.is_empty()
+    }
+
+    pub fn is_failed(&self) -> bool {
+        self.failed
+    }
+}
+
+/// файлы, которых нет в кэше или которые поменялись
+///
+/// заодно удаляет из кэша все, чего больше нет в манифесте. считает хэши, поэтому не для сетевого потока
+pub fn outdated(root: &Path, files: &[AssetFile]) -> Vec<String> {
+    let expected: HashSet<&Path> = files.iter().map(|file| file.path.as_path()).collect();
+
+    prune(root, &expected);
+
+    files
+        .iter()
+        .filter(|file| {
+            std::fs::read(&file.path)
+                .map(|bytes| !matches(file, &bytes))
+                .unwrap_or(true)
+        })
+        .map(|file| file.name.clone())
+        .collect()
+}
+
+fn matches(file: &AssetFile, bytes: &[u8]) -> bool {
+    bytes.len() as u64 == file.size && Sha256::digest(bytes).as_slice() == file.hash
+}
+
+fn prune(dir: &Path, expected: &HashSet<&Path>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => {
+                prune(&path, expected);
+                // удалится только пустая
+                let _ = std::fs::remove_dir(&path);
+            }
+
+            Ok(_) if !expected.contains(path.as_path()) => {
+                let _ = std::fs::remove_file(&path);
+            }
+
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+
+    /// папка кэша одного теста, удаляется при выходе из теста, даже если assert упал
+    struct TempRoot(PathBuf);
+
+    impl TempRoot {
+        fn new(name: &str) -> TempRoot {
+            let root = std::env::temp_dir().join(format!(
+                "cef_asset_cache_{}_{}",
+                name,
+                std::process::id()
+            ));
+            let _ = std::fs::remove_dir_all(&root);
+            TempRoot(root)
+        }
+    }
+
+    impl std::ops::Deref for TempRoot {
+        type Target = Path;
+
+        fn deref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempRoot {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn manifest<'a>(files: &[(&'a str, &'a [u8])]) -> packets::AssetManifest<'a> {
+        let files = files
+            .iter()
+            .map(|&(path, bytes)| packets::AssetFile {
+                path: Cow::Borrowed(path),
+                size: bytes.len() as u64,
+                hash: Cow::Owned(Sha256::digest(bytes).to_vec()),
+            })
+            .collect();
+
+        packets::AssetManifest { files }
+    }
+
+    fn chunk<'a>(path: &'a str, index: u32, total: u32, data: &'a [u8]) -> packets::AssetChunk<'a> {
+        packets::AssetChunk {
+            path: Cow::Borrowed(path),
+            index,
+            total,
+            data: Cow::Borrowed(data),
+        }
+    }
+
+    fn requested(root: &Path, files: &[(&str, &[u8])]) -> AssetCache {
+        let mut cache = AssetCache::new(root.to_path_buf(), &manifest(files)).unwrap();
+        let names: Vec<String> = files.iter().map(|(path, _)| path.to_string()).collect();
+        cache.request(&names);
+        cache
+    }
+
+    #[test]
+    fn downloads_chunks_in_any_order() {
+        let root = TempRoot::new("order");
+        let mut cache = requested(&root, &[("ui/index.html", b"hello world")]);
+
+        assert!(!cache.push_chunk(&chunk("ui/index.html", 1, 2, b"world")));
+        assert!(cache.push_chunk(&chunk("ui/index.html", 0, 2, b"hello ")));
+
+        assert!(cache.is_finished());
+        assert!(!cache.is_failed());
+        assert_eq!(
+            std::fs::read(root.join("ui").join("index.html")).unwrap(),
+            b"hello world"
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_chunk() {
+        let root = TempRoot::new("duplicate");
+        let mut cache = requested(&root, &[("a.txt", b"abcdef")]);
+
+        assert!(!cache.push_chunk(&chunk("a.txt", 0, 3, b"ab")));
+        assert!(cache.push_chunk(&chunk("a.txt", 0, 3, b"ab")));
+
+        assert!(cache.is_finished());
+        assert!(cache.is_failed());
+    }
+
+    #[test]
+    fn rejects_out_of_range_chunk() {
+        let root = TempRoot::new("range");
+        let mut cache = requested(&root, &[("a.txt", b"abcdef")]);
+
+        assert!(cache.push_chunk(&chunk("a.txt", 2, 2, b"abc")));
+        assert!(cache.is_failed());
+
+        // кусков больше, чем байт в файле
+        let mut cache = requested(&root, &[("a.txt", b"ab")]);
+
+        assert!(cache.push_chunk(&chunk("a.txt", 0, 3, b"a")));
+        assert!(cache.is_failed());
+    }
+
+    #[test]
+    fn rejects_changed_total() {
+        let root = TempRoot::new("total");
+        let mut cache = requested(&root, &[("a.txt", b"abcdef")]);
+
+        assert!(!cache.push_chunk(&chunk("a.txt", 0, 3, b"ab")));
+        assert!(cache.push_chunk(&chunk("a.txt", 1, 2, b"cd")));
+        assert!(cache.is_failed());
+    }
+
+    #[test]
+    fn rejects_oversize_data() {
+        let root = TempRoot::new("oversize");
+        let mut cache = requested(&root, &[("a.txt", b"abcd")]);
+
+        assert!(!cache.push_chunk(&chunk("a.txt", 0, 2, b"abc")));
+        assert!(cache.push_chunk(&chunk("a.txt", 1, 2, b"def")));
+
+        assert!(cache.is_failed());
+        assert!(!root.join("a.txt").exists());
+    }
+
+    #[test]
+    fn rejects_hash_mismatch() {
+        let root = TempRoot::new("hash");
+        let mut cache = requested(&root, &[("a.txt", b"abcd")]);
+
+        assert!(cache.push_chunk(&chunk("a.txt", 0, 1, b"abce")));
+
+        assert!(cache.is_failed());
+        assert!(!root.join("a.txt").exists());
+    }
+
+    #[test]
+    fn fails_file_missing_on_server() {
+        let root = TempRoot::new("missing");
+        let mut cache = requested(&root, &[("a.txt", b"abcd")]);
+
+        assert!(cache.push_chunk(&chunk("a.txt", 0, 0, b"")));
+
+        assert!(cache.is_finished());
+        assert!(cache.is_failed());
+    }
+
+    #[test]
+    fn ignores_unrequested_files() {
+        let root = TempRoot::new("unrequested");
+        let mut cache =
+            AssetCache::new(root.to_path_buf(), &manifest(&[("a.txt", b"abcd")])).unwrap();
+
+        assert!(!cache.push_chunk(&chunk("a.txt", 0, 1, b"abcd")));
+        assert!(!cache.push_chunk(&chunk("b.txt", 0, 1, b"abcd")));
+
+        assert!(!cache.is_failed());
+        assert!(!root.join("a.txt").exists());
+    }
+
+    #[test]
+    fn rejects_bad_manifest_paths() {
+        let root = TempRoot::new("manifest");
+
+        for path in ["../a.txt", "/a.txt", "C:/a.txt", "ui\\a.txt"] {
+            let result = AssetCache::new(root.to_path_buf(), &manifest(&[(path, b"abcd")]));
+            assert!(matches!(result, Err(ManifestError::BadPath(_))), "{}", path);
+        }
+    }
+
+    #[test]
+    fn outdated_skips_valid_files_and_prunes_the_rest() {
+        let root = TempRoot::new("outdated");
+        let cache = AssetCache::new(
+            root.to_path_buf(),
+            &manifest(&[
+                ("ui/keep.txt", b"keep"),
+                ("ui/changed.txt", b"new"),
+                ("missing.txt", b"missing"),
+            ]),
+        )
+        .unwrap();
+
+        std::fs::create_dir_all(root.join("ui")).unwrap();
+        std::fs::create_dir_all(root.join("old").join("nested")).unwrap();
+        std::fs::write(root.join("ui").join("keep.txt"), b"keep").unwrap();
+        std::fs::write(root.join("ui").join("changed.txt"), b"old").unwrap();
+        std::fs::write(root.join("ui").join("extra.txt"), b"extra").unwrap();
+        std::fs::write(root.join("old").join("nested").join("file.txt"), b"old").unwrap();
+
+        let mut outdated = outdated(&root, &cache.files());
+        outdated.sort();
+
+        assert_eq!(outdated, ["missing.txt", "ui/changed.txt"]);
+        assert!(root.join("ui").join("keep.txt").exists());
+        assert!(!root.join("ui").join("extra.txt").exists());
+        assert!(!root.join("old").exists());
+    }
+}
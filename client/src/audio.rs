@@ -12,6 +12,9 @@ use std::time::{Duration, Instant};
 pub const MAX_DISTANCE: f32 = 50.0;
 pub const REFRENCE_DISTANCE: f32 = 15.0;
 
+/// источник браузера на экране, играет без позиции в мире
+pub const SCREEN_SOURCE: i32 = -1;
+
 #[derive(Copy, Clone)]
 pub struct BrowserAudioSettings {
     pub max_distance: f32,
@@ -24,6 +27,7 @@ pub struct Audio {
     paused: AtomicBool,
     terminate: AtomicBool,
     streams: Mutex<HashMap<u32, Vec<AudioStream>>>, // browser_id -> streams
+    browser_gain: Mutex<HashMap<u32, f32>>,         // browser_id -> gain
 }
 
 pub struct AudioStream {
@@ -162,6 +166,7 @@ impl Audio {
             paused: AtomicBool::new(false),
             terminate: AtomicBool::new(false),
             streams: Mutex::new(HashMap::new()),
+            browser_gain: Mutex::new(HashMap::new()),
         };
 
         let audio = Arc::new(audio);
@@ -267,9 +272,12 @@ impl Audio {
     pub fn remove_all_streams(&self, browser: u32) {
         let mut streams = self.streams.lock().unwrap();
         streams.remove(&browser);
+
+        self.browser_gain.lock().unwrap().remove(&browser);
     }
 
     pub fn add_source(&self, browser: u32, object_id: i32) {
+        let gain = self.browser_gain(browser);
         let mut streams = self.streams.lock().unwrap();
 
         if let Some(entries) = streams.get_mut(&browser) {
@@ -278,18 +286,8 @@ impl Audio {
                     return;
                 }
 
-                let mut source = self.context.new_streaming_source().unwrap();
-                let plain_data = vec![0.0f32; entry.max_frames as usize];
-                let mut buffers = Vec::with_capacity(50);
-
-                for _ in 0..50 {
-                    let buffer = self
-                        .context
-                        .new_buffer::<Mono<f32>, _>(plain_data.as_slice(), entry.sample_rate)
-                        .unwrap();
-
-                    buffers.push(buffer);
-                }
+                let mut audio_source = self.new_source(entry);
+                let source = &mut audio_source.source;
 
                 source.set_distance_model(DistanceModel::ExponentClamped);
                 source.set_max_distance(MAX_DISTANCE);
@@ -297,18 +295,82 @@ impl Audio {
                 source.set_rolloff_factor(7.0);
                 source.set_relative(false);
                 source.set_max_gain(0.0); //
-
-                let audio_source = AudioSource {
-                    source,
-                    buffers,
-                    muted: true,
-                };
+                source.set_gain(gain);
 
                 entry.sources.insert(object_id, audio_source);
             });
         }
     }
 
+    /// для браузеров на экране: звук всегда рядом со слушателем
+    pub fn add_screen_source(&self, browser: u32) {
+        let gain = self.browser_gain(browser);
+        let mut streams = self.streams.lock().unwrap();
+
+        if let Some(entries) = streams.get_mut(&browser) {
+            entries.iter_mut().for_each(|entry| {
+                if entry.sources.contains_key(&SCREEN_SOURCE) {
+                    return;
+                }
+
+                let mut audio_source = self.new_source(entry);
+                let source = &mut audio_source.source;
+
+                source.set_distance_model(DistanceModel::None);
+                source.set_relative(true);
+                source.set_position([0.0, 0.0, 0.0]);
+                source.set_max_gain(1.0);
+                source.set_gain(gain);
+
+                audio_source.muted = false;
+
+                entry.sources.insert(SCREEN_SOURCE, audio_source);
+            });
+        }
+    }
+
+    /// громкость отдельного браузера, 0.0 - 1.0
+    pub fn set_browser_gain(&self, browser: u32, gain: f32) {
+        self.browser_gain.lock().unwrap().insert(browser, gain);
+
+        let mut streams = self.streams.lock().unwrap();
+
+        if let Some(entries) = streams.get_mut(&browser) {
+            entries.iter_mut().for_each(|entry| {
+                entry
+                    .sources
+                    .values_mut()
+                    .for_each(|source| source.source.set_gain(gain));
+            });
+        }
+    }
+
+    fn browser_gain(&self, browser: u32) -> f32 {
+        let gains = self.browser_gain.lock().unwrap();
+        gains.get(&browser).copied().unwrap_or(1.0)
+    }
+
+    fn new_source(&self, entry: &AudioStream) -> AudioSource {
+        let source = self.context.new_streaming_source().unwrap();
+        let plain_data = vec![0.0f32; entry.max_frames as usize];
+        let mut buffers = Vec::with_capacity(50);
+
+        for _ in 0..50 {
+            let buffer = self
+                .context
+                .new_buffer::<Mono<f32>, _>(plain_data.as_slice(), entry.sample_rate)
+                .unwrap();
+
+            buffers.push(buffer);
+        }
+
+        AudioSource {
+            source,
+            buffers,
+            muted: true,
+        }
+    }
+
     pub fn remove_source(&self, browser: u32, object_id: i32) {
         let mut streams = self.streams.lock().unwrap();
 
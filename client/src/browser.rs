@@ -2,4 +2,5 @@ pub mod assets_scheme;
 pub mod cef;
 pub mod client;
 pub mod manager;
+pub mod screenshot;
 pub mod view;
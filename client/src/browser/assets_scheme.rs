@@ -10,17 +10,22 @@ use cef_sys::{
     cef_resource_read_callback_t, cef_resource_skip_callback_t, cef_response_t,
     cef_scheme_handler_factory_t, cef_scheme_options_t, cef_scheme_registrar_t, cef_string_t,
 };
+use parking_lot::Mutex;
 use percent_encoding::percent_decode_str;
 use url::Url;
 
 pub const ASSET_SCHEME: &str = "sampcef";
 const ASSET_HOST: &str = "assets";
+/// файлы, скачанные с текущего сервера
+const SERVER_ASSET_HOST: &str = "server";
 const BLANK_URL: &str = "about:blank";
 const ASSET_SCHEME_OPTIONS: i32 = cef_scheme_options_t::CEF_SCHEME_OPTION_STANDARD
     | cef_scheme_options_t::CEF_SCHEME_OPTION_SECURE
     | cef_scheme_options_t::CEF_SCHEME_OPTION_CORS_ENABLED
     | cef_scheme_options_t::CEF_SCHEME_OPTION_FETCH_ENABLED;
 
+static SERVER_ASSETS_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
 #[repr(C)]
 struct Wrapper<T, I> {
     cef_object: T,
@@ -178,19 +183,56 @@ pub fn register_custom_scheme(registrar: *mut cef_scheme_registrar_t) {
 
 pub fn register_scheme_handler_factory() {
     let scheme = CefString::new(ASSET_SCHEME);
-    let domain = CefString::new(ASSET_HOST);
-    let factory = create_scheme_handler_factory();
-
-    let result = unsafe {
-        cef_sys::cef_register_scheme_handler_factory(
-            scheme.as_cef_string(),
-            domain.as_cef_string(),
-            factory,
-        )
-    };
 
-    if result == 0 {
-        log::error!("failed to register asset scheme handler factory");
+    for host in [ASSET_HOST, SERVER_ASSET_HOST] {
+        let domain = CefString::new(host);
+        let factory = create_scheme_handler_factory();
+
+        let result = unsafe {
+            cef_sys::cef_register_scheme_handler_factory(
+                scheme.as_cef_string(),
+                domain.as_cef_string(),
+                factory,
+            )
+        };
+
+        if result == 0 {
+            log::error!(
+                "failed to register asset scheme handler factory for {}",
+                host
+            );
+        }
+    }
+}
+
+/// папка, которую отдает `sampcef://server/`, `None` пока сервер ничего не прислал
+pub fn set_server_assets_dir(dir: Option<PathBuf>) {
+    *SERVER_ASSETS_DIR.lock() = dir;
+}
+
+/// путь файла из манифеста сервера внутри `root`
+///
+/// принимаются только относительные пути через `/` без `..`, все остальное отбрасывается
+pub fn sanitize_asset_path(root: &Path, path: &str) -> Option<PathBuf> {
+    if path.is_empty() || path.contains(['\\', ':', '\0']) {
+        return None;
+    }
+
+    let relative = Path::new(path);
+
+    if !relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return None;
+    }
+
+    let root = normalize_path(root)?;
+    let normalized = normalize_path(&root.join(relative))?;
+
+    match normalized.strip_prefix(&root) {
+        Ok(rest) if !rest.as_os_str().is_empty() => Some(normalized),
+        _ => None,
     }
 }
 
@@ -487,11 +529,21 @@ fn file_path_to_asset_url(
     Some(url.into())
 }
 
+fn asset_root(host: &str) -> Option<PathBuf> {
+    match host {
+        ASSET_HOST => Some(crate::utils::assets_dir()),
+        SERVER_ASSET_HOST => SERVER_ASSETS_DIR.lock().clone(),
+        _ => None,
+    }
+}
+
 fn resolve_asset_request_path(url: &Url) -> Option<PathBuf> {
-    if url.scheme() != ASSET_SCHEME || url.host_str() != Some(ASSET_HOST) {
+    if url.scheme() != ASSET_SCHEME {
         return None;
     }
 
+    let root = asset_root(url.host_str()?)?;
+
     let mut relative = PathBuf::new();
 
     for segment in url.path().split('/') {
@@ -515,10 +567,10 @@ fn resolve_asset_request_path(url: &Url) -> Option<PathBuf> {
         relative.push("index.html");
     }
 
-    let candidate = crate::utils::assets_dir().join(relative);
+    let candidate = root.join(relative);
     let normalized = normalize_path(&candidate)?;
     canonicalize_with_missing_tail(&normalized).and_then(|secured| {
-        let root = fs::canonicalize(crate::utils::assets_dir()).ok()?;
+        let root = fs::canonicalize(&root).ok()?;
         secured.strip_prefix(&root).ok()?;
         Some(secured)
     })
@@ -642,3 +694,58 @@ fn mime_type_for_path(path: &Path) -> &'static str {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root() -> PathBuf {
+        std::env::temp_dir().join("cef_assets")
+    }
+
+    #[test]
+    fn accepts_relative_paths() {
+        let root = root();
+
+        assert_eq!(
+            sanitize_asset_path(&root, "ui/index.html"),
+            Some(root.join("ui").join("index.html"))
+        );
+
+        assert_eq!(
+            sanitize_asset_path(&root, "./ui/./app.js"),
+            Some(root.join("ui").join("app.js"))
+        );
+    }
+
+    #[test]
+    fn rejects_parent_dirs() {
+        let root = root();
+
+        for path in ["..", "../secret", "ui/../../secret", "ui/../index.html"] {
+            assert_eq!(sanitize_asset_path(&root, path), None, "{}", path);
+        }
+    }
+
+    #[test]
+    fn rejects_absolute_and_drive_paths() {
+        let root = root();
+
+        for path in [
+            "/etc/passwd",
+            "C:/Windows/win.ini",
+            "C:win.ini",
+            "//server/share/file",
+        ] {
+            assert_eq!(sanitize_asset_path(&root, path), None, "{}", path);
+        }
+    }
+
+    #[test]
+    fn rejects_backslashes_and_empty_paths() {
+        let root = root();
+
+        for path in ["", ".", "./", "ui\\index.html", "..\\secret", "ui/a\0b"] {
+            assert_eq!(sanitize_asset_path(&root, path), None, "{:?}", path);
+        }
+    }
+}
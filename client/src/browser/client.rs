@@ -2,7 +2,7 @@ use std::collections::HashSet;
 use std::ffi::CString;
 use std::sync::{
     Arc,
-    atomic::{AtomicBool, Ordering},
+    atomic::{AtomicBool, AtomicI32, Ordering},
 };
 use std::time::Duration;
 
@@ -17,16 +17,21 @@ use cef::handlers::context_menu::ContextMenuHandler;
 use cef::handlers::lifespan::LifespanHandler;
 use cef::handlers::load::LoadHandler;
 use cef::handlers::render::{DirtyRects, PaintElement, RenderHandler};
+use cef::handlers::request::RequestHandler;
 use cef::process_message::ProcessMessage;
 use cef::types::list::ValueType;
+use cef::types::string::CefString;
 
-use cef_sys::{cef_audio_parameters_t, cef_rect_t};
+use cef_sys::{cef_audio_parameters_t, cef_rect_t, cef_screen_info_t, cef_termination_status_t};
 
 use client_api::utils::handle_result;
 
+use messages::packets::{EventValue, NavigationAction};
+
 use crate::app::Event;
 use crate::audio::Audio;
-use crate::browser::view::View;
+use crate::browser::screenshot::Frame;
+use crate::browser::view::{BrowserRect, View};
 use crate::external::{CallbackList, EXTERNAL_BREAK};
 
 struct DrawData {
@@ -76,10 +81,17 @@ pub struct WebClient {
     prev_hidden_flag: AtomicBool,
     closing: AtomicBool,
     listen_keys: AtomicBool,
+    layer: AtomicI32,
+    max_fps: AtomicI32,
+    frame_rate: AtomicI32, // выставленная у cef частота
     pub view: Mutex<View>,
+    layout: Mutex<Option<BrowserRect>>,
+    zoom_level: Mutex<f64>,
     draw_data: Mutex<DrawData>,
     browser: Mutex<Option<Browser>>,
-    audio: Option<Arc<Audio>>, // static
+    audio: Arc<Audio>, // static
+    volume: Mutex<f32>,
+    muted: AtomicBool,
     event_tx: Sender<Event>,
     callbacks: CallbackList,
     object_list: Mutex<HashSet<i32>>,
@@ -107,16 +119,21 @@ impl LifespanHandler for WebClientRef {
         log::trace!("LifespanHandler::on_after_created. hidden: {}", hidden);
 
         self.0.hide(hidden);
+        self.0.apply_zoom();
     }
 
     fn on_before_close(&self, _: Browser) {
         log::trace!("LifespanHandler::on_before_close");
 
-        let mut browser = self.0.browser.lock();
+        {
+            let mut browser = self.0.browser.lock();
 
-        if let Some(browser) = browser.take() {
-            browser.host().close_dev_tools();
+            if let Some(browser) = browser.take() {
+                browser.host().close_dev_tools();
+            }
         }
+
+        handle_result(self.0.event_tx.send(Event::BrowserDestroyed(self.0.id)));
     }
 }
 
@@ -126,6 +143,7 @@ impl Client for WebClientRef {
     type ContextMenuHandler = Self;
     type LoadHandler = Self;
     type AudioHandler = Self;
+    type RequestHandler = Self;
 
     fn lifespan_handler(&self) -> Option<Self> {
         Some(self.clone())
@@ -143,12 +161,12 @@ impl Client for WebClientRef {
         Some(self.clone())
     }
 
+    fn request_handler(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+
     fn audio_handler(&self) -> Option<Self> {
-        if self.0.is_extern {
-            Some(self.clone())
-        } else {
-            None
-        }
+        Some(self.clone())
     }
 
     fn on_process_message(
@@ -193,6 +211,85 @@ impl Client for WebClientRef {
                 return true;
             }
 
+            "set_layer" => {
+                let args = msg.argument_list();
+
+                let layer = match args.get_type(0) {
+                    ValueType::Integer => args.integer(0),
+                    ValueType::Double => args.double(0) as i32,
+                    _ => return true,
+                };
+
+                let event = Event::SetBrowserLayer(self.0.id, layer);
+                handle_result(self.0.event_tx.send(event));
+
+                return true;
+            }
+
+            "set_zoom" => {
+                let args = msg.argument_list();
+
+                let level = match args.get_type(0) {
+                    ValueType::Integer => args.integer(0) as f64,
+                    ValueType::Double => args.double(0),
+                    _ => return true,
+                };
+
+                self.0.set_zoom(level);
+
+                return true;
+            }
+
+            "set_volume" => {
+                let args = msg.argument_list();
+
+                let volume = match args.get_type(0) {
+                    ValueType::Integer => args.integer(0) as f32,
+                    ValueType::Double => args.double(0) as f32,
+                    _ => return true,
+                };
+
+                self.0.set_volume(Some(volume), None);
+
+                return true;
+            }
+
+            "set_muted" => {
+                let args = msg.argument_list();
+
+                let muted = match args.get_type(0) {
+                    ValueType::Integer => args.integer(0) == 1,
+                    ValueType::Bool => args.bool(0),
+                    _ => return true,
+                };
+
+                self.0.set_volume(None, Some(muted));
+
+                return true;
+            }
+
+            "take_screenshot" => {
+                let event = Event::TakeScreenshot(self.0.id, None);
+                handle_result(self.0.event_tx.send(event));
+
+                return true;
+            }
+
+            "js_result" => {
+                let args = msg.argument_list();
+
+                let event = Event::JavaScriptResult(
+                    self.0.id,
+                    args.integer(0) as u32,
+                    args.bool(1),
+                    args.string(2).to_string(),
+                );
+
+                handle_result(self.0.event_tx.send(event));
+
+                return true;
+            }
+
             "emit_event" => {
                 let args = msg.argument_list();
 
@@ -214,6 +311,7 @@ impl Client for WebClientRef {
                 }
 
                 let mut arguments = String::new();
+                let mut values = Vec::with_capacity(args.len().saturating_sub(1));
 
                 for idx in 1..args.len() {
                     let arg = match args.get_type(idx) {
@@ -224,14 +322,40 @@ impl Client for WebClientRef {
                         _ => "CEF_NULL".to_string(),
                     };
 
+                    // null остается пустым значением, сервер отбросит его в типизированных событиях
+                    let value = match args.get_type(idx) {
+                        ValueType::String => EventValue {
+                            string_value: Some(arg.clone().into()),
+                            ..Default::default()
+                        },
+
+                        ValueType::Bool => EventValue {
+                            integer_value: Some(args.bool(idx) as i32),
+                            ..Default::default()
+                        },
+
+                        ValueType::Integer => EventValue {
+                            integer_value: Some(args.integer(idx)),
+                            ..Default::default()
+                        },
+
+                        ValueType::Double => EventValue {
+                            float_value: Some(args.double(idx) as f32),
+                            ..Default::default()
+                        },
+
+                        _ => EventValue::default(),
+                    };
+
                     arguments.push_str(&arg);
+                    values.push(value);
 
                     if idx != args.len() - 1 {
                         arguments.push(' ');
                     }
                 }
 
-                let event = Event::EmitEventOnServer(event_name, arguments);
+                let event = Event::EmitEventOnServer(self.0.id, event_name, arguments, values);
                 handle_result(self.0.event_tx.send(event));
             }
 
@@ -251,7 +375,17 @@ impl ContextMenuHandler for WebClientRef {
 impl RenderHandler for WebClientRef {
     fn view_rect(&self, _: Browser, rect: &mut cef_rect_t) {
         let texture = self.0.view.lock();
-        *rect = texture.rect();
+        *rect = texture.page_rect();
+    }
+
+    fn screen_info(&self, _: Browser, info: &mut cef_screen_info_t) -> bool {
+        let texture = self.0.view.lock();
+
+        info.device_scale_factor = texture.scale();
+        info.rect = texture.page_rect();
+        info.available_rect = texture.page_rect();
+
+        true
     }
 
     fn on_popup_show(&self, _: Browser, show: bool) {
@@ -265,9 +399,18 @@ impl RenderHandler for WebClientRef {
     }
 
     fn on_popup_size(&self, _: Browser, rect: &cef_rect_t) {
+        // прямоугольник в css пикселях, а буфер попапа приходит в пикселях текстуры
+        let scale = self.0.view.lock().scale();
+        let rect = cef_rect_t {
+            x: (rect.x as f32 * scale).round() as i32,
+            y: (rect.y as f32 * scale).round() as i32,
+            width: (rect.width as f32 * scale).ceil() as i32,
+            height: (rect.height as f32 * scale).ceil() as i32,
+        };
+
         let mut draw_data = self.0.draw_data.lock();
 
-        draw_data.popup_rect = *rect;
+        draw_data.popup_rect = rect;
 
         draw_data
             .popup_buffer
@@ -356,10 +499,65 @@ impl LoadHandler for WebClientRef {
         );
 
         if frame.is_main() {
+            // хромиум хранит зум для каждого сайта, после перехода он может сброситься
+            self.0.apply_zoom();
+
             let event = Event::BrowserCreated(self.0.id, status_code);
             handle_result(self.0.event_tx.send(event));
+
+            let event = Event::BrowserLoaded(self.0.id, frame.url().to_string(), status_code);
+            handle_result(self.0.event_tx.send(event));
+        }
+    }
+
+    fn on_load_error(
+        &self, _browser: Browser, frame: Frame, error_code: i32, error_text: CefString,
+        failed_url: CefString,
+    ) {
+        log::trace!(
+            "LoadHandler::on_load_error. id: {} code: {} url: {}",
+            self.0.id,
+            error_code,
+            failed_url
+        );
+
+        if frame.is_main() {
+            let event = Event::BrowserLoadError(
+                self.0.id,
+                error_code,
+                error_text.to_string(),
+                failed_url.to_string(),
+            );
+
+            handle_result(self.0.event_tx.send(event));
         }
     }
+
+    fn on_loading_state_change(
+        &self, _browser: Browser, is_loading: bool, can_go_back: bool, can_go_forward: bool,
+    ) {
+        let event = Event::LoadingStateChanged(self.0.id, is_loading, can_go_back, can_go_forward);
+
+        handle_result(self.0.event_tx.send(event));
+    }
+}
+
+impl RequestHandler for WebClientRef {
+    fn on_render_process_terminated(
+        &self, _browser: Browser, status: cef_termination_status_t::Type,
+    ) {
+        log::error!(
+            "RequestHandler::on_render_process_terminated. id: {} status: {}",
+            self.0.id,
+            status
+        );
+
+        handle_result(
+            self.0
+                .event_tx
+                .send(Event::RendererCrashed(self.0.id, status)),
+        );
+    }
 }
 
 impl AudioHandler for WebClientRef {
@@ -378,10 +576,10 @@ impl AudioHandler for WebClientRef {
     fn on_audio_stream_packet(
         &self, _browser: Browser, stream_id: i32, data: *mut *const f32, frames: i32, pts: i64,
     ) {
-        if let Some(audio) = self.0.audio.as_ref() {
-            unsafe {
-                audio.append_pcm(self.0.id, stream_id, data, frames, pts as u64);
-            }
+        unsafe {
+            self.0
+                .audio
+                .append_pcm(self.0.id, stream_id, data, frames, pts as u64);
         }
     }
 
@@ -389,26 +587,29 @@ impl AudioHandler for WebClientRef {
         &self, _browser: Browser, stream_id: i32, channels: i32, _channel_layout: i32,
         sample_rate: i32, frames_per_buffer: i32,
     ) {
-        if let Some(audio) = self.0.audio.as_ref() {
-            audio.create_stream(
-                self.0.id,
-                stream_id,
-                channels,
-                sample_rate,
-                frames_per_buffer,
-            );
+        let audio = &self.0.audio;
+
+        audio.create_stream(
+            self.0.id,
+            stream_id,
+            channels,
+            sample_rate,
+            frames_per_buffer,
+        );
+
+        if self.0.is_extern {
             let objects = self.0.object_list.lock();
 
             for &object_id in objects.iter() {
                 audio.add_source(self.0.id, object_id);
             }
+        } else {
+            audio.add_screen_source(self.0.id);
         }
     }
 
     fn on_audio_stream_stopped(&self, _browser: Browser, stream_id: i32) {
-        if let Some(audio) = self.0.audio.as_ref() {
-            audio.remove_stream(self.0.id, stream_id);
-        }
+        self.0.audio.remove_stream(self.0.id, stream_id);
     }
 
     fn on_audio_stream_error(&self, _browser: Browser, error: String) {
@@ -418,7 +619,9 @@ impl AudioHandler for WebClientRef {
 
 impl WebClient {
     #[allow(clippy::arc_with_non_send_sync)]
-    pub fn new(id: u32, cbs: CallbackList, event_tx: Sender<Event>) -> Arc<WebClient> {
+    pub fn new(
+        id: u32, cbs: CallbackList, event_tx: Sender<Event>, audio: Arc<Audio>,
+    ) -> Arc<WebClient> {
         let rect = crate::utils::client_rect();
 
         log::trace!("crate::utils::client_rect: {:?}", rect);
@@ -431,13 +634,20 @@ impl WebClient {
             prev_hidden_flag: AtomicBool::new(false),
             closing: AtomicBool::new(false),
             listen_keys: AtomicBool::new(false),
+            layer: AtomicI32::new(0),
+            max_fps: AtomicI32::new(0),
+            frame_rate: AtomicI32::new(0),
             view: Mutex::new(view),
+            layout: Mutex::new(None),
+            zoom_level: Mutex::new(0.0),
             draw_data: Mutex::new(DrawData::new()),
             browser: Mutex::new(None),
             callbacks: cbs,
             object_list: Mutex::new(HashSet::new()),
             is_extern: false,
-            audio: None,
+            audio,
+            volume: Mutex::new(1.0),
+            muted: AtomicBool::new(false),
             event_tx,
             id,
             rendered: (Mutex::new(0), Condvar::new()),
@@ -457,13 +667,20 @@ impl WebClient {
             prev_hidden_flag: AtomicBool::new(false),
             closing: AtomicBool::new(false),
             listen_keys: AtomicBool::new(false),
+            layer: AtomicI32::new(0),
+            max_fps: AtomicI32::new(0),
+            frame_rate: AtomicI32::new(0),
             view: Mutex::new(view),
+            layout: Mutex::new(None),
+            zoom_level: Mutex::new(0.0),
             draw_data: Mutex::new(DrawData::new()),
             browser: Mutex::new(None),
             callbacks: cbs,
             object_list: Mutex::new(HashSet::new()),
             is_extern: true,
-            audio: Some(audio),
+            audio,
+            volume: Mutex::new(1.0),
+            muted: AtomicBool::new(false),
             event_tx,
             id,
             rendered: (Mutex::new(0), Condvar::new()),
@@ -498,10 +715,11 @@ impl WebClient {
 
             if self.is_extern() {
             } else {
-                let rect = crate::utils::client_rect();
+                let rect = self.layout_rect(crate::utils::client_rect());
 
                 view.make_active();
-                view.make_display(rect[0], rect[1]);
+                view.make_display(rect.width as usize, rect.height as usize);
+                view.set_position(rect.x, rect.y);
 
                 self.notify_was_resized();
             }
@@ -510,15 +728,96 @@ impl WebClient {
         self.restore_hide_status();
     }
 
+    /// для внешних браузеров размер текстуры, для остальных размер экрана
     #[inline]
     pub fn resize(&self, width: usize, height: usize) {
         let mut view = self.view.lock();
-        view.resize(self.is_extern(), width, height);
+
+        if self.is_extern() {
+            view.resize(true, width, height);
+        } else {
+            let rect = self.layout_rect([width, height]);
+
+            view.resize(false, rect.width as usize, rect.height as usize);
+            view.set_position(rect.x, rect.y);
+        }
+
         self.notify_was_resized();
 
         self.signal_rendered_current();
     }
 
+    /// `None` растягивает браузер на весь экран
+    pub fn set_layout(&self, layout: Option<BrowserRect>) {
+        *self.layout.lock() = layout;
+
+        let screen = crate::utils::client_rect();
+        self.resize(screen[0], screen[1]);
+    }
+
+    fn layout_rect(&self, screen: [usize; 2]) -> cef_rect_t {
+        match *self.layout.lock() {
+            Some(layout) => layout.resolve(screen),
+            None => cef_rect_t {
+                x: 0,
+                y: 0,
+                width: screen[0] as i32,
+                height: screen[1] as i32,
+            },
+        }
+    }
+
+    /// переводит координаты экрана в координаты страницы
+    pub fn to_local(&self, x: i32, y: i32) -> (i32, i32) {
+        let view = self.view.lock();
+        let rect = view.screen_rect();
+        let scale = view.scale();
+
+        (
+            ((x - rect.x) as f32 / scale) as i32,
+            ((y - rect.y) as f32 / scale) as i32,
+        )
+    }
+
+    /// уровень зума cef: 0.0 - 100%, каждая единица - еще 20%
+    pub fn set_zoom(&self, level: f64) {
+        *self.zoom_level.lock() = level;
+        self.apply_zoom();
+    }
+
+    fn apply_zoom(&self) {
+        let level = *self.zoom_level.lock();
+
+        if let Some(host) = self.browser().map(|browser| browser.host()) {
+            host.set_zoom_level(level);
+        }
+    }
+
+    /// страница рисуется в `scale` раз плотнее, размер на экране не меняется
+    pub fn set_device_scale(&self, scale: f32) {
+        {
+            let mut view = self.view.lock();
+
+            if view.scale() == scale {
+                return;
+            }
+
+            view.set_scale(scale);
+        }
+
+        if self.is_extern() {
+            let rect = self.view.lock().rect();
+            self.resize(rect.width as usize, rect.height as usize);
+        } else {
+            let screen = crate::utils::client_rect();
+            self.resize(screen[0], screen[1]);
+        }
+
+        if let Some(host) = self.browser().map(|browser| browser.host()) {
+            host.notify_screen_info_changed();
+        }
+    }
+
     fn notify_was_resized(&self) {
         let browser = self.browser.lock();
 
@@ -651,6 +950,27 @@ impl WebClient {
         }
     }
 
+    /// громкость и мьют самого браузера, не зависят от скрытия
+    pub fn set_volume(&self, volume: Option<f32>, muted: Option<bool>) {
+        let mut current = self.volume.lock();
+
+        if let Some(volume) = volume.filter(|volume| volume.is_finite()) {
+            *current = volume.clamp(0.0, 1.0);
+        }
+
+        if let Some(muted) = muted {
+            self.muted.store(muted, Ordering::SeqCst);
+        }
+
+        let gain = if self.muted.load(Ordering::SeqCst) {
+            0.0
+        } else {
+            *current
+        };
+
+        self.audio.set_browser_gain(self.id, gain);
+    }
+
     pub fn add_object(&self, object_id: i32) {
         let mut objects = self.object_list.lock();
         objects.insert(object_id);
@@ -705,6 +1025,42 @@ impl WebClient {
         }
     }
 
+    pub fn navigate(&self, action: NavigationAction) {
+        let Some(browser) = self.browser() else {
+            return;
+        };
+
+        match action {
+            NavigationAction::RELOAD => browser.reload(),
+            NavigationAction::RELOAD_IGNORE_CACHE => browser.reload_ignore_cache(),
+            NavigationAction::GO_BACK => browser.go_back(),
+            NavigationAction::GO_FORWARD => browser.go_forward(),
+            NavigationAction::STOP => browser.stop_load(),
+        }
+    }
+
+    /// без `request_id` результат никому не нужен, поэтому выполняем напрямую,
+    /// иначе отдаем код рендеру, он вернет результат через `js_result`
+    pub fn execute_javascript(&self, code: &str, request_id: Option<u32>) {
+        let Some(frame) = self.browser().map(|browser| browser.main_frame()) else {
+            return;
+        };
+
+        match request_id {
+            Some(request_id) => {
+                let msg = ProcessMessage::create("execute_js");
+                let args = msg.argument_list();
+
+                args.set_string(0, &CefString::new(code));
+                args.set_integer(1, request_id as i32);
+
+                frame.send_process_message(ProcessId::Renderer, msg);
+            }
+
+            None => frame.execute_java_script(code, "", 0),
+        }
+    }
+
     pub fn id(&self) -> u32 {
         self.id
     }
@@ -729,4 +1085,63 @@ impl WebClient {
     pub fn set_always_listen_keys(&self, listen: bool) {
         self.listen_keys.store(listen, Ordering::SeqCst);
     }
+
+    pub fn max_fps(&self) -> i32 {
+        self.max_fps.load(Ordering::SeqCst)
+    }
+
+    pub fn set_max_fps(&self, max_fps: i32) {
+        self.max_fps.store(max_fps, Ordering::SeqCst);
+    }
+
+    /// cef дергаем только когда частота поменялась
+    pub fn set_frame_rate(&self, frame_rate: i32) {
+        let Some(host) = self.browser().map(|browser| browser.host()) else {
+            return;
+        };
+
+        if self.frame_rate.swap(frame_rate, Ordering::SeqCst) != frame_rate {
+            host.set_windowless_frame_rate(frame_rate);
+
+            log::trace!("browser {} frame rate: {}", self.id, frame_rate);
+        }
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.hidden.load(Ordering::SeqCst)
+    }
+
+    /// браузер на экране, но его прямоугольник целиком за краем экрана
+    pub fn is_offscreen(&self) -> bool {
+        if self.is_extern() {
+            return false;
+        }
+
+        let rect = self.view.lock().screen_rect();
+        let [width, height] = crate::utils::client_rect();
+
+        rect.x >= width as i32
+            || rect.y >= height as i32
+            || rect.x + rect.width <= 0
+            || rect.y + rect.height <= 0
+    }
+
+    /// копия последнего нарисованного кадра, пустая если браузер еще ничего не рисовал
+    pub fn frame(&self) -> Frame {
+        let draw_data = self.draw_data.lock();
+
+        Frame {
+            bytes: draw_data.view_buffer.clone(),
+            width: draw_data.width,
+            height: draw_data.height,
+        }
+    }
+
+    pub fn layer(&self) -> i32 {
+        self.layer.load(Ordering::SeqCst)
+    }
+
+    pub fn set_layer(&self, layer: i32) {
+        self.layer.store(layer, Ordering::SeqCst);
+    }
 }
@@ -1,6 +1,8 @@
 use crate::app::{Event, ExternalBrowser};
 use crate::audio::{Audio, BrowserAudioSettings};
 use crate::browser::client::WebClient;
+use crate::browser::screenshot::Frame;
+use crate::browser::view::BrowserRect;
 use crate::external::{BrowserReadyCallback, CallbackList};
 
 use cef::handlers::render::PaintElement;
@@ -8,6 +10,8 @@ use cef::types::list::List;
 use cef::types::string::CefString;
 use cef_sys::{cef_event_flags_t, cef_key_event_t, cef_mouse_button_type_t, cef_mouse_event_t};
 
+use messages::packets::NavigationAction;
+
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 
@@ -16,6 +20,10 @@ use client_api::gta::rw::rwplcore::RwSurfaceProperties;
 
 use crossbeam_channel::Sender;
 
+const MIN_FPS: i32 = 15;
+const LOW_POWER_FPS: i32 = 30;
+const BACKGROUND_FPS: i32 = 1;
+
 #[derive(Debug, Clone, Copy, Hash, Ord, PartialOrd, Eq, PartialEq)]
 pub enum MouseKey {
     Left,
@@ -55,7 +63,8 @@ pub struct Manager {
     mouse: Mouse,
     view_width: usize,
     view_height: usize,
-    prev_fps: u64,
+    game_fps: i32,
+    low_power: bool,
     cef_running: bool,
 }
 
@@ -78,7 +87,8 @@ impl Manager {
             clients_on_txd: Vec::new(),
             view_height: 0,
             view_width: 0,
-            prev_fps: 60,
+            game_fps: 60,
+            low_power: false,
             input_corrupted: false,
             do_not_draw: false,
             cef_running: false,
@@ -99,7 +109,7 @@ impl Manager {
             render_mode
         );
 
-        let client = WebClient::new(id, cbs, self.event_tx.clone());
+        let client = WebClient::new(id, cbs, self.event_tx.clone(), self.audio.clone());
 
         log::trace!("crate::browser::cef::create_browser");
 
@@ -197,19 +207,91 @@ impl Manager {
             return;
         }
 
-        if let Some(&focus) = self.focused.as_ref() {
-            for client in self.clients.values().filter(|client| client.id() != focus) {
-                client.draw();
-            }
+        for client in self.layered_clients() {
+            client.draw();
+        }
+    }
 
-            if let Some(focused) = self.clients.get(&focus) {
-                focused.draw();
-            }
-        } else {
-            for client in self.clients.values() {
-                client.draw();
+    /// снизу вверх: по слою, сфокусированный выше остальных в своем слое, затем по id
+    fn layered_clients(&self) -> Vec<&Arc<WebClient>> {
+        let mut clients: Vec<_> = self.clients.values().collect();
+
+        clients.sort_by_key(|client| {
+            let focused = self.focused == Some(client.id());
+            (client.layer(), focused, client.id())
+        });
+
+        clients
+    }
+
+    fn layer(&self, id: u32) -> i32 {
+        self.clients
+            .get(&id)
+            .map(|client| client.layer())
+            .unwrap_or(0)
+    }
+
+    /// следующий из очереди фокуса: с самым верхним слоем, при равенстве - кто раньше попросил
+    fn pop_focus_queue(&mut self) -> Option<u32> {
+        let idx = self
+            .focused_queue
+            .iter()
+            .enumerate()
+            .max_by_key(|&(idx, &id)| (self.layer(id), std::cmp::Reverse(idx)))
+            .map(|(idx, _)| idx)?;
+
+        self.focused_queue.remove(idx)
+    }
+
+    /// браузер из очереди с более высоким слоем забирает фокус у текущего
+    fn raise_focus(&mut self) {
+        let Some(focused) = self.focused else {
+            return;
+        };
+
+        let focused_layer = self.layer(focused);
+
+        if self
+            .focused_queue
+            .iter()
+            .any(|&id| self.layer(id) > focused_layer)
+        {
+            self.focused = self.pop_focus_queue();
+            self.focused_queue.push_front(focused);
+        }
+    }
+
+    /// сообщает серверу, если фокус на самом деле перешел, а не просто встал в очередь
+    fn notify_focus(&self, previous: Option<u32>) {
+        if previous == self.focused {
+            return;
+        }
+
+        // закрытый браузер сервер и так удалит
+        if let Some(id) = previous.filter(|id| self.clients.contains_key(id)) {
+            let _ = self.event_tx.send(Event::BrowserFocusChanged(id, false));
+        }
+
+        if let Some(id) = self.focused {
+            let _ = self.event_tx.send(Event::BrowserFocusChanged(id, true));
+        }
+    }
+
+    pub fn set_browser_layer(&mut self, id: u32, layer: i32) {
+        let previous = self.focused;
+
+        if let Some(client) = self.clients.get(&id) {
+            client.set_layer(layer);
+            self.raise_focus();
+
+            // сфокусированный браузер мог опуститься ниже ждущих фокуса
+            if let Some(focused) = self.focused.filter(|&focused| focused == id) {
+                self.focused_queue.push_front(focused);
+                self.focused = self.pop_focus_queue();
             }
         }
+
+        self.notify_focus(previous);
     }
 
     #[inline]
@@ -278,6 +360,7 @@ impl Manager {
             self.mouse.y = y;
 
             let keys = &self.mouse.keys;
+            let (x, y) = client.to_local(x, y);
 
             let mut event = cef_mouse_event_t { x, y, modifiers: 0 };
 
@@ -308,11 +391,8 @@ impl Manager {
         {
             self.mouse.keys.insert(button, is_down);
 
-            let event = cef_mouse_event_t {
-                x: self.mouse.x,
-                y: self.mouse.y,
-                modifiers: 0,
-            };
+            let (x, y) = client.to_local(self.mouse.x, self.mouse.y);
+            let event = cef_mouse_event_t { x, y, modifiers: 0 };
 
             let key = match button {
                 MouseKey::Left => cef_mouse_button_type_t::MBT_LEFT,
@@ -333,7 +413,8 @@ impl Manager {
         if let Some(client) = self.focused.as_ref().and_then(|id| self.clients.get(id))
             && let Some(host) = client.browser().map(|browser| browser.host())
         {
-            host.send_mouse_wheel(self.mouse.x, self.mouse.y, delta);
+            let (x, y) = client.to_local(self.mouse.x, self.mouse.y);
+            host.send_mouse_wheel(x, y, delta);
         }
     }
 
@@ -350,8 +431,9 @@ impl Manager {
             host.send_keyboard_event(event);
         } else {
             for host in self
-                .clients
-                .values()
+                .layered_clients()
+                .into_iter()
+                .rev()
                 .filter(|client| client.always_listen_keys())
                 .filter_map(|client| client.browser().map(|browser| browser.host()))
             {
@@ -362,40 +444,65 @@ impl Manager {
 
     pub fn trigger_event(&self, event_name: &str, list: List) {
         for client in self.clients.values() {
-            if let Some(frame) = client.browser().map(|browser| browser.main_frame()) {
-                let name = CefString::new(event_name);
-                let msg = cef::process_message::ProcessMessage::create("trigger_event");
+            Self::send_trigger_event(client, event_name, list.clone());
+        }
+    }
 
-                let args = msg.argument_list();
-                args.set_string(0, &name);
-                args.set_list(1, list.clone());
+    pub fn trigger_browser_event(&self, id: u32, event_name: &str, list: List) {
+        if let Some(client) = self.clients.get(&id) {
+            Self::send_trigger_event(client, event_name, list);
+        }
+    }
 
-                frame.send_process_message(cef::ProcessId::Renderer, msg);
-            }
+    fn send_trigger_event(client: &WebClient, event_name: &str, list: List) {
+        if let Some(frame) = client.browser().map(|browser| browser.main_frame()) {
+            let name = CefString::new(event_name);
+            let msg = cef::process_message::ProcessMessage::create("trigger_event");
+
+            let args = msg.argument_list();
+            args.set_string(0, &name);
+            args.set_list(1, list);
+
+            frame.send_process_message(cef::ProcessId::Renderer, msg);
         }
     }
 
     pub fn close_browser(&mut self, id: u32, force_close: bool) {
+        let previous = self.focused;
+
         if let Some(client) = self.clients.remove(&id) {
             self.internal_close(client, force_close);
         }
 
         self.cleanup_focus_after_close(id);
+        self.notify_focus(previous);
     }
 
     #[inline]
     pub fn hide_browser(&self, id: u32, hide: bool) {
         if let Some(browser) = self.clients.get(&id) {
+            let changed = browser.is_hidden() != hide;
+
             browser.hide(hide);
+            browser.set_frame_rate(self.frame_rate(browser));
+
+            if changed {
+                let _ = self
+                    .event_tx
+                    .send(Event::BrowserVisibilityChanged(id, hide));
+            }
         }
     }
 
     pub fn browser_focus(&mut self, id: u32, focus: bool) {
+        let previous = self.focused;
+
         if self.clients.contains_key(&id) {
             if focus {
                 if let Some(&cur_id) = self.focused.as_ref() {
                     if cur_id != id {
                         self.focused_queue.push_back(id);
+                        self.raise_focus();
                     }
                 } else {
                     self.focused = Some(id);
@@ -406,7 +513,7 @@ impl Manager {
                 .filter(|focused| **focused == id)
                 .is_some()
             {
-                self.focused = self.focused_queue.pop_front();
+                self.focused = self.pop_focus_queue();
             } else {
                 self.focused_queue
                     .iter()
@@ -414,6 +521,8 @@ impl Manager {
                     .map(|idx| self.focused_queue.remove(idx));
             }
         }
+
+        self.notify_focus(previous);
     }
 
     #[inline(always)]
@@ -449,6 +558,7 @@ impl Manager {
         if self.do_not_draw != donot {
             self.do_not_draw = donot;
             self.temporary_hide(donot);
+            self.update_frame_rates();
         }
     }
 
@@ -487,12 +597,53 @@ impl Manager {
             });
     }
 
+    pub fn set_browser_rect(&self, browser_id: u32, rect: Option<BrowserRect>) {
+        if let Some(client) = self.clients.get(&browser_id)
+            && !client.is_extern()
+        {
+            client.set_layout(rect);
+            client.set_frame_rate(self.frame_rate(client));
+        }
+    }
+
+    pub fn set_zoom(&self, browser_id: u32, level: f64, scale: Option<f32>) {
+        if let Some(client) = self.clients.get(&browser_id) {
+            client.set_zoom(level);
+
+            if let Some(scale) = scale {
+                client.set_device_scale(scale);
+            }
+        }
+    }
+
+    pub fn set_browser_volume(&self, browser_id: u32, volume: Option<f32>, muted: Option<bool>) {
+        if let Some(client) = self.clients.get(&browser_id) {
+            client.set_volume(volume, muted);
+        }
+    }
+
+    pub fn browser_frame(&self, browser_id: u32) -> Option<Frame> {
+        self.clients.get(&browser_id).map(|client| client.frame())
+    }
+
     pub fn load_url(&self, browser_id: u32, url: &str) {
         if let Some(client) = self.clients.get(&browser_id) {
             client.load_url(url)
         }
     }
 
+    pub fn navigate(&self, browser_id: u32, action: NavigationAction) {
+        if let Some(client) = self.clients.get(&browser_id) {
+            client.navigate(action)
+        }
+    }
+
+    pub fn execute_javascript(&self, browser_id: u32, code: &str, request_id: Option<u32>) {
+        if let Some(client) = self.clients.get(&browser_id) {
+            client.execute_javascript(code, request_id)
+        }
+    }
+
     pub fn call_browser_ready(&self, browser_id: u32) {
         if let Some(callbacks) = self.ready_callbacks.get(&browser_id) {
             callbacks.iter().for_each(|cb| cb(browser_id))
@@ -518,17 +669,49 @@ impl Manager {
 
     #[inline(always)]
     pub fn update_fps(&mut self, fps: u64) {
-        let fps_small = fps as i32;
-        let fps_small = std::cmp::max(15, fps_small); // std::cmp::min(60, fps_small)
+        self.game_fps = fps as i32;
+        self.update_frame_rates();
+    }
 
-        for browser in self.clients.values().filter_map(|client| client.browser()) {
-            let host = browser.host();
+    pub fn update_frame_rates(&self) {
+        for client in self.clients.values() {
+            client.set_frame_rate(self.frame_rate(client));
+        }
+    }
 
-            if self.prev_fps != fps {
-                host.set_windowless_frame_rate(fps_small);
-                self.prev_fps = fps;
-            }
+    /// частота игры, но не больше лимита браузера и режима экономии.
+    /// скрытые и ушедшие за экран браузеры почти не рисуются
+    fn frame_rate(&self, client: &WebClient) -> i32 {
+        if client.is_hidden() || client.is_offscreen() {
+            return BACKGROUND_FPS;
         }
+
+        let mut fps = std::cmp::max(MIN_FPS, self.game_fps);
+
+        if client.max_fps() > 0 {
+            fps = std::cmp::min(fps, client.max_fps());
+        }
+
+        if self.low_power {
+            fps = std::cmp::min(fps, LOW_POWER_FPS);
+        }
+
+        std::cmp::max(BACKGROUND_FPS, fps)
+    }
+
+    /// 0 - без ограничения
+    pub fn set_browser_max_fps(&self, browser_id: u32, max_fps: i32) {
+        if let Some(client) = self.clients.get(&browser_id) {
+            client.set_max_fps(std::cmp::max(0, max_fps));
+            client.set_frame_rate(self.frame_rate(client));
+        }
+    }
+
+    pub fn set_low_power_mode(&mut self, enabled: bool) {
+        log::trace!("manager::set_low_power_mode({})", enabled);
+
+        self.low_power = enabled;
+        self.update_frame_rates();
     }
 
     pub fn close_all_browsers(&mut self) {
@@ -632,7 +815,7 @@ impl Manager {
         if self.focused == Some(closed_id) || focused_stale {
             self.focused = None;
 
-            while let Some(next_id) = self.focused_queue.pop_front() {
+            while let Some(next_id) = self.pop_focus_queue() {
                 if self.clients.contains_key(&next_id) {
                     self.focused = Some(next_id);
                     break;
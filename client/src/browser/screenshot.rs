@@ -0,0 +1,172 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// последний нарисованный кадр браузера в BGRA, как его отдает cef
+pub struct Frame {
+    pub bytes: Vec<u8>,
+    pub width: usize,
+    pub height: usize,
+}
+
+#[derive(Debug)]
+pub enum ScreenshotError {
+    /// браузер еще ничего не нарисовал
+    Empty,
+    BufferSize { expected: usize, len: usize },
+    Encode(png::EncodingError),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for ScreenshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScreenshotError::Empty => write!(f, "no painted frame"),
+            ScreenshotError::BufferSize { expected, len } => {
+                write!(f, "buffer is {} bytes, expected {}", len, expected)
+            }
+            ScreenshotError::Encode(err) => write!(f, "png: {}", err),
+            ScreenshotError::Io(err) => write!(f, "io: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ScreenshotError {}
+
+impl From<png::EncodingError> for ScreenshotError {
+    fn from(err: png::EncodingError) -> Self {
+        ScreenshotError::Encode(err)
+    }
+}
+
+impl From<std::io::Error> for ScreenshotError {
+    fn from(err: std::io::Error) -> Self {
+        ScreenshotError::Io(err)
+    }
+}
+
+impl Frame {
+    pub fn encode_png(&self) -> Result<Vec<u8>, ScreenshotError> {
+        encode_png(&self.bytes, self.width, self.height)
+    }
+}
+
+/// кодирует BGRA буфер без отступов между строками в RGBA png
+pub fn encode_png(bgra: &[u8], width: usize, height: usize) -> Result<Vec<u8>, ScreenshotError> {
+    if width == 0 || height == 0 {
+        return Err(ScreenshotError::Empty);
+    }
+
+    let expected = width * height * 4;
+
+    if bgra.len() < expected {
+        return Err(ScreenshotError::BufferSize {
+            expected,
+            len: bgra.len(),
+        });
+    }
+
+    let rgba: Vec<u8> = bgra[..expected]
+        .chunks_exact(4)
+        .flat_map(|pixel| [pixel[2], pixel[1], pixel[0], pixel[3]])
+        .collect();
+
+    let mut output = Vec::new();
+
+    {
+        let mut encoder = png::Encoder::new(&mut output, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_compression(png::Compression::Fast);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&rgba)?;
+        writer.finish()?;
+    }
+
+    Ok(output)
+}
+
+/// сохраняет в `dir` как `browser_<id>_<unix ms>.png`
+pub fn save(dir: &Path, browser_id: u32, png: &[u8]) -> Result<PathBuf, ScreenshotError> {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_millis())
+        .unwrap_or(0);
+
+    std::fs::create_dir_all(dir)?;
+
+    let path = dir.join(format!("browser_{}_{}.png", browser_id, time));
+    std::fs::write(&path, png)?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(bytes: &[u8]) -> (png::OutputInfo, Vec<u8>) {
+        let decoder = png::Decoder::new(std::io::Cursor::new(bytes));
+        let mut reader = decoder.read_info().unwrap();
+        let mut buffer = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut buffer).unwrap();
+
+        buffer.truncate(info.buffer_size());
+
+        (info, buffer)
+    }
+
+    #[test]
+    fn swaps_bgra_to_rgba() {
+        #[rustfmt::skip]
+        let bgra = [
+            0x00, 0x00, 0xFF, 0xFF,   0x00, 0xFF, 0x00, 0x80,
+            0xFF, 0x00, 0x00, 0x00,   0x10, 0x20, 0x30, 0x40,
+        ];
+
+        let png = encode_png(&bgra, 2, 2).unwrap();
+        let (info, rgba) = decode(&png);
+
+        assert_eq!((info.width, info.height), (2, 2));
+        assert_eq!(info.color_type, png::ColorType::Rgba);
+
+        #[rustfmt::skip]
+        let expected = [
+            0xFF, 0x00, 0x00, 0xFF,   0x00, 0xFF, 0x00, 0x80,
+            0x00, 0x00, 0xFF, 0x00,   0x30, 0x20, 0x10, 0x40,
+        ];
+
+        assert_eq!(rgba, expected);
+    }
+
+    #[test]
+    fn ignores_trailing_bytes() {
+        let bgra = vec![0x7F; 3 * 2 * 4 + 16];
+
+        let png = encode_png(&bgra, 3, 2).unwrap();
+        let (info, rgba) = decode(&png);
+
+        assert_eq!((info.width, info.height), (3, 2));
+        assert_eq!(rgba, vec![0x7F; 3 * 2 * 4]);
+    }
+
+    #[test]
+    fn rejects_short_buffer() {
+        let err = encode_png(&[0; 15], 2, 2).unwrap_err();
+
+        assert!(matches!(
+            err,
+            ScreenshotError::BufferSize {
+                expected: 16,
+                len: 15
+            }
+        ));
+    }
+
+    #[test]
+    fn rejects_empty_frame() {
+        assert!(matches!(encode_png(&[], 0, 0), Err(ScreenshotError::Empty)));
+        assert!(matches!(encode_png(&[0; 4], 1, 0), Err(ScreenshotError::Empty)));
+    }
+}
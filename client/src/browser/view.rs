@@ -4,9 +4,62 @@ use client_api::gta::rw;
 use client_api::gta::rw::rwcore::{RwRaster, RwTexture};
 use client_api::gta::rw::rwplcore::{self, RwRGBA};
 use client_api::gta::sprite::Sprite;
+use messages::packets::Anchor;
 use std::ops::{Deref, DerefMut};
 use std::ptr::NonNull;
 
+/// положение браузера на экране, пересчитывается при смене разрешения.
+/// `x` и `y` - смещение от точки привязки (вправо и вниз), в процентах экрана, если `percent`
+#[derive(Debug, Clone, Copy)]
+pub struct BrowserRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub anchor: Anchor,
+    pub percent: bool,
+}
+
+impl BrowserRect {
+    pub fn resolve(&self, screen: [usize; 2]) -> cef_rect_t {
+        let screen_width = screen[0] as f32;
+        let screen_height = screen[1] as f32;
+
+        let scale = |value: f32, full: f32| {
+            if self.percent {
+                value * full / 100.0
+            } else {
+                value
+            }
+        };
+
+        let width = scale(self.width, screen_width).round().max(1.0);
+        let height = scale(self.height, screen_height).round().max(1.0);
+
+        let (anchor_x, anchor_y) = match self.anchor {
+            Anchor::TOP_LEFT => (0.0, 0.0),
+            Anchor::TOP => (0.5, 0.0),
+            Anchor::TOP_RIGHT => (1.0, 0.0),
+            Anchor::LEFT => (0.0, 0.5),
+            Anchor::CENTER => (0.5, 0.5),
+            Anchor::RIGHT => (1.0, 0.5),
+            Anchor::BOTTOM_LEFT => (0.0, 1.0),
+            Anchor::BOTTOM => (0.5, 1.0),
+            Anchor::BOTTOM_RIGHT => (1.0, 1.0),
+        };
+
+        let x = (screen_width - width) * anchor_x + scale(self.x, screen_width);
+        let y = (screen_height - height) * anchor_y + scale(self.y, screen_height);
+
+        cef_rect_t {
+            x: x.round() as i32,
+            y: y.round() as i32,
+            width: width as i32,
+            height: height as i32,
+        }
+    }
+}
+
 pub struct RwLockGuard<'a> {
     bytes: &'a mut [u8],
     pub pitch: usize,
@@ -112,15 +165,7 @@ impl SpriteContainer {
     }
 
     #[inline]
-    pub fn draw(&mut self) {
-        let client = crate::utils::client_rect();
-        let rect = CRect {
-            top: 0.0,
-            left: 0.0,
-            right: client[0] as f32,
-            bottom: client[1] as f32,
-        };
-
+    pub fn draw(&mut self, rect: CRect) {
         let color = RwRGBA {
             red: 0xFF,
             green: 0xFF,
@@ -148,9 +193,9 @@ enum ViewContainer {
 }
 
 impl ViewContainer {
-    fn draw(&mut self) {
+    fn draw(&mut self, rect: CRect) {
         if let ViewContainer::Display(sprite) = self {
-            sprite.draw();
+            sprite.draw(rect);
         }
     }
 
@@ -173,6 +218,11 @@ pub struct View {
     container: Option<ViewContainer>,
     width: usize,
     height: usize,
+    page_width: usize,
+    page_height: usize,
+    scale: f32,
+    x: i32,
+    y: i32,
     active: bool,
 }
 
@@ -182,13 +232,17 @@ impl View {
             container: None,
             width: 0,
             height: 0,
+            page_width: 0,
+            page_height: 0,
+            scale: 1.0,
+            x: 0,
+            y: 0,
             active: true,
         }
     }
 
     pub fn make_display(&mut self, width: usize, height: usize) {
-        let width = std::cmp::max(1, width);
-        let height = std::cmp::max(1, height);
+        let (width, height) = self.scaled_size(width, height);
 
         self.destroy_previous();
 
@@ -201,6 +255,8 @@ impl View {
     pub fn make_inactive(&mut self) {
         self.destroy_previous();
         self.set_size(1, 1);
+        self.page_width = 1;
+        self.page_height = 1;
         self.active = false;
     }
 
@@ -210,8 +266,15 @@ impl View {
 
     #[inline]
     pub fn draw(&mut self) {
+        let rect = CRect {
+            top: self.y as f32,
+            left: self.x as f32,
+            right: (self.x + self.width as i32) as f32,
+            bottom: (self.y + self.height as i32) as f32,
+        };
+
         if let Some(rw) = self.container.as_mut() {
-            rw.draw()
+            rw.draw(rect)
         }
     }
 
@@ -281,14 +344,17 @@ impl View {
         }
 
         let should_replace = self.active && self.container.is_none();
-
-        if self.width == width && self.height == height && !should_replace {
+        let page_size = (self.page_width, self.page_height);
+        let (width, height) = self.scaled_size(width, height);
+
+        if self.width == width
+            && self.height == height
+            && page_size == (self.page_width, self.page_height)
+            && !should_replace
+        {
             return;
         }
 
-        let width = std::cmp::max(1, width);
-        let height = std::cmp::max(1, height);
-
         self.destroy_previous();
         self.set_size(width, height);
 
@@ -320,6 +386,54 @@ impl View {
         }
     }
 
+    /// размер страницы в css пикселях, текстура больше в `scale` раз
+    pub fn page_rect(&self) -> cef_rect_t {
+        cef_rect_t {
+            width: std::cmp::max(1, self.page_width) as i32,
+            height: std::cmp::max(1, self.page_height) as i32,
+            x: 0,
+            y: 0,
+        }
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// после смены нужно пересоздать текстуру через `resize`
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+    }
+
+    /// считает размер страницы под экранный размер и возвращает размер текстуры,
+    /// в которую cef нарисует страницу с текущим `scale`
+    fn scaled_size(&mut self, width: usize, height: usize) -> (usize, usize) {
+        let page_width = std::cmp::max(1, (width as f32 / self.scale).round() as usize);
+        let page_height = std::cmp::max(1, (height as f32 / self.scale).round() as usize);
+
+        self.page_width = page_width;
+        self.page_height = page_height;
+
+        (
+            (page_width as f32 * self.scale).ceil() as usize,
+            (page_height as f32 * self.scale).ceil() as usize,
+        )
+    }
+
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    /// где браузер нарисован на экране
+    pub fn screen_rect(&self) -> cef_rect_t {
+        cef_rect_t {
+            x: self.x,
+            y: self.y,
+            ..self.rect()
+        }
+    }
+
     pub fn rwtexture(&mut self) -> Option<NonNull<RwTexture>> {
         self.container.as_mut().and_then(|rw| rw.texture())
     }
@@ -40,6 +40,7 @@ struct ExtPlugin {
     #[allow(dead_code)]
     // Keep the library loaded for plugin callbacks.
     library: Library,
+    name: String,
     initialize: Option<Symbol<'static, extern "C" fn(*mut InternalApi)>>,
     mainloop: Option<Symbol<'static, extern "C" fn()>>,
     dxreset: Option<Symbol<'static, extern "C" fn()>>,
@@ -67,6 +68,8 @@ struct InternalApi {
     cef_browser_ready: unsafe extern "C" fn(browser: u32) -> bool,
     cef_on_browser_ready: unsafe extern "C" fn(browser: u32, callback: BrowserReadyCallback),
     cef_gta_window_active: unsafe extern "C" fn() -> bool,
+    cef_set_browser_max_fps: unsafe extern "C" fn(browser: u32, max_fps: i32),
+    cef_set_low_power_mode: unsafe extern "C" fn(enabled: bool),
 }
 
 fn make_api_struct() -> InternalApi {
@@ -85,6 +88,8 @@ fn make_api_struct() -> InternalApi {
         cef_browser_ready,
         cef_on_browser_ready,
         cef_gta_window_active,
+        cef_set_browser_max_fps,
+        cef_set_low_power_mode,
     }
 }
 
@@ -134,8 +139,15 @@ pub fn initialize(event_tx: Sender<Event>, manager: Arc<Mutex<Manager>>) -> Call
                                 .get::<extern "C" fn(u32, i32)>(b"cef_browser_created")
                                 .ok();
 
+                            let name = dir
+                                .path()
+                                .file_stem()
+                                .map(|stem| stem.to_string_lossy().into_owned())
+                                .unwrap_or_default();
+
                             let plugin = ExtPlugin {
                                 library: lib,
+                                name,
                                 initialize,
                                 mainloop,
                                 dxreset,
@@ -212,6 +224,17 @@ pub fn call_disconnect() {
     }
 }
 
+pub fn plugin_names() -> Vec<String> {
+    ExternalManager::get()
+        .map(|ext| {
+            ext.plugins
+                .iter()
+                .map(|plugin| plugin.name.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 pub fn window_active(active: bool) {
     if let Some(ext) = ExternalManager::get() {
         ext.window_active.store(active, Ordering::SeqCst);
@@ -376,3 +399,17 @@ unsafe extern "C" fn cef_gta_window_active() -> bool {
         .map(|ext| ext.window_active.load(Ordering::SeqCst))
         .unwrap_or(false)
 }
+
+unsafe extern "C" fn cef_set_browser_max_fps(browser: u32, max_fps: i32) {
+    if let Some(external) = ExternalManager::get() {
+        let event = Event::SetBrowserMaxFps(browser, max_fps);
+        let _ = external.event_tx.send(event);
+    }
+}
+
+unsafe extern "C" fn cef_set_low_power_mode(enabled: bool) {
+    if let Some(external) = ExternalManager::get() {
+        let event = Event::SetLowPowerMode(enabled);
+        let _ = external.event_tx.send(event);
+    }
+}
@@ -1,14 +1,21 @@
 use client_api::utils::handle_result;
 use crossbeam_channel::{Receiver, Sender};
-use messages::packets;
+use messages::{Limits, packets};
 use net::{Event as SocketEvent, PeerId, Socket};
-use quick_protobuf::deserialize_from_slice;
 
 use crate::app::{Event, ExternalBrowser};
+use crate::asset_cache::{self, AssetCache};
+use crate::browser::assets_scheme;
+use crate::browser::view::BrowserRect;
 
 use std::net::SocketAddr;
 use std::time::{Duration, Instant};
 
+const MIN_DEVICE_SCALE: f32 = 0.25;
+const MAX_DEVICE_SCALE: f32 = 4.0;
+/// скриншот уходит на сервер кусками, чтобы не упираться в лимит размера пакета
+const SCREENSHOT_CHUNK_SIZE: usize = 256 * 1024;
+
 #[allow(dead_code)]
 struct Packet {
     peer: PeerId,
@@ -92,7 +99,11 @@ struct Network {
     event_tx: Sender<Event>,
     event_rx: Receiver<Event>,
 
+    limits: Limits,
     timings: Instant,
+    assets: Option<AssetCache>,
+    /// номера полученных пакетов, уходят одним `Got` в конце тика
+    acks: Vec<u32>,
 }
 
 impl Network {
@@ -106,12 +117,19 @@ impl Network {
             socket,
             event_tx,
             event_rx,
+            limits: Limits::default(),
+            assets: None,
+            acks: Vec::new(),
         })
     }
 
     fn handle_packet(&mut self, packet: packets::Packet) {
         use packets::PacketId::*;
 
+        if let Some(seq) = packet.seq {
+            self.acks.push(seq);
+        }
+
         match packet.packet_id {
             OPEN_CONNECTION => {
                 if let ConnectionState::Auth(_, _, peer) = &self.connection_state {
@@ -124,88 +142,175 @@ impl Network {
                 }
             }
 
+            BATCH => self.handle_batch(&packet.bytes, 0),
+
             JOIN_RESPONSE => {
-                deserialize_from_slice(&packet.bytes)
+                messages::decode(&packet.bytes, &self.limits)
                     .map(|packet| self.handle_join_response(packet))
                     .ok();
             }
 
             CREATE_BROWSER => {
-                deserialize_from_slice(&packet.bytes)
+                messages::decode(&packet.bytes, &self.limits)
                     .map(|packet| self.handle_create_browser(packet))
                     .ok();
             }
 
             DESTROY_BROWSER => {
-                deserialize_from_slice(&packet.bytes)
+                messages::decode(&packet.bytes, &self.limits)
                     .map(|packet| self.handle_destroy_browser(packet))
                     .ok();
             }
 
             HIDE_BROWSER => {
-                deserialize_from_slice(&packet.bytes)
+                messages::decode(&packet.bytes, &self.limits)
                     .map(|packet| self.handle_hide_browser(packet))
                     .ok();
             }
 
             ALWAYS_LISTEN_KEYS => {
-                deserialize_from_slice(&packet.bytes)
+                messages::decode(&packet.bytes, &self.limits)
                     .map(|packet| self.handle_always_listen_keys(packet))
                     .ok();
             }
 
             FOCUS_BROWSER => {
-                deserialize_from_slice(&packet.bytes)
+                messages::decode(&packet.bytes, &self.limits)
                     .map(|packet| self.handle_focus_browser(packet))
                     .ok();
             }
 
             EMIT_EVENT => {
-                deserialize_from_slice(&packet.bytes)
+                messages::decode(&packet.bytes, &self.limits)
                     .map(|packet| self.handle_emit_event(packet))
                     .ok();
             }
 
             CREATE_EXTERNAL_BROWSER => {
-                deserialize_from_slice(&packet.bytes)
+                messages::decode(&packet.bytes, &self.limits)
                     .map(|packet| self.handle_create_external_browser(packet))
                     .ok();
             }
 
             APPEND_TO_OBJECT => {
-                deserialize_from_slice(&packet.bytes)
+                messages::decode(&packet.bytes, &self.limits)
                     .map(|packet| self.handle_append_to_object(packet))
                     .ok();
             }
 
             REMOVE_FROM_OBJECT => {
-                deserialize_from_slice(&packet.bytes)
+                messages::decode(&packet.bytes, &self.limits)
                     .map(|packet| self.handle_remove_from_object(packet))
                     .ok();
             }
 
             TOGGLE_DEV_TOOLS => {
-                deserialize_from_slice(&packet.bytes)
+                messages::decode(&packet.bytes, &self.limits)
                     .map(|packet| self.handle_toggle_dev_tools(packet))
                     .ok();
             }
 
             SET_AUDIO_SETTINGS => {
-                deserialize_from_slice(&packet.bytes)
+                messages::decode(&packet.bytes, &self.limits)
                     .map(|packet| self.handle_set_audio_settings(packet))
                     .ok();
             }
 
+            EXECUTE_JAVASCRIPT => {
+                messages::decode(&packet.bytes, &self.limits)
+                    .map(|packet| self.handle_execute_javascript(packet))
+                    .ok();
+            }
+
             LOAD_URL => {
-                deserialize_from_slice(&packet.bytes)
+                messages::decode(&packet.bytes, &self.limits)
                     .map(|packet| self.handle_load_url(packet))
                     .ok();
             }
 
+            SET_BROWSER_RECT => {
+                messages::decode(&packet.bytes, &self.limits)
+                    .map(|packet| self.handle_set_browser_rect(packet))
+                    .ok();
+            }
+
+            SET_BROWSER_MAX_FPS => {
+                messages::decode(&packet.bytes, &self.limits)
+                    .map(|packet| self.handle_set_browser_max_fps(packet))
+                    .ok();
+            }
+
+            ASSET_MANIFEST => {
+                messages::decode(&packet.bytes, &self.limits)
+                    .map(|packet| self.handle_asset_manifest(packet))
+                    .ok();
+            }
+
+            ASSET_CHUNK => {
+                messages::decode(&packet.bytes, &self.limits)
+                    .map(|packet| self.handle_asset_chunk(packet))
+                    .ok();
+            }
+
+            TAKE_SCREENSHOT => {
+                messages::decode(&packet.bytes, &self.limits)
+                    .map(|packet| self.handle_take_screenshot(packet))
+                    .ok();
+            }
+
+            SET_LOW_POWER_MODE => {
+                messages::decode(&packet.bytes, &self.limits)
+                    .map(|packet| self.handle_set_low_power_mode(packet))
+                    .ok();
+            }
+
+            SET_BROWSER_AUDIO => {
+                messages::decode(&packet.bytes, &self.limits)
+                    .map(|packet| self.handle_set_browser_audio(packet))
+                    .ok();
+            }
+
+            SET_ZOOM => {
+                messages::decode(&packet.bytes, &self.limits)
+                    .map(|packet| self.handle_set_zoom(packet))
+                    .ok();
+            }
+
+            SET_BROWSER_LAYER => {
+                messages::decode(&packet.bytes, &self.limits)
+                    .map(|packet| self.handle_set_browser_layer(packet))
+                    .ok();
+            }
+
+            NAVIGATE_BROWSER => {
+                messages::decode(&packet.bytes, &self.limits)
+                    .map(|packet| self.handle_navigate_browser(packet))
+                    .ok();
+            }
+
             _ => (),
         }
     }
 
+    /// пакеты внутри проверяются один раз, при декодировании батча
+    fn handle_batch(&mut self, bytes: &[u8], depth: usize) {
+        let batch = match messages::decode_nested::<packets::Batch>(bytes, &self.limits, depth) {
+            Ok(batch) => batch,
+            Err(e) => {
+                log::trace!("malformed batch from the server: {}", e);
+                return;
+            }
+        };
+
+        for packet in batch.packets {
+            if packet.packet_id == packets::PacketId::BATCH {
+                self.handle_batch(&packet.bytes, depth + 1);
+            } else {
+                self.handle_packet(packet);
+            }
+        }
+    }
+
     fn handle_join_response(&mut self, packet: packets::JoinResponse) {
         if let ConnectionState::Auth(addr, _, peer) = self.connection_state {
             if packet.success {
@@ -262,10 +367,11 @@ impl Network {
             }
         }
 
-        handle_result(
-            self.event_tx
-                .send(Event::EmitEvent(packet.event_name.to_string(), list)),
-        );
+        handle_result(self.event_tx.send(Event::EmitEvent(
+            packet.event_name.to_string(),
+            list,
+            packet.browser_id,
+        )));
     }
 
     fn handle_hide_browser(&mut self, packet: packets::HideBrowser) {
@@ -327,10 +433,124 @@ impl Network {
         handle_result(self.event_tx.send(event));
     }
 
+    fn handle_set_browser_rect(&mut self, packet: packets::SetBrowserRect) {
+        // нулевой размер возвращает браузер на весь экран
+        let rect = if packet.width > 0.0 && packet.height > 0.0 {
+            Some(BrowserRect {
+                x: packet.x,
+                y: packet.y,
+                width: packet.width,
+                height: packet.height,
+                anchor: packet.anchor,
+                percent: packet.percent,
+            })
+        } else {
+            None
+        };
+
+        handle_result(
+            self.event_tx
+                .send(Event::SetBrowserRect(packet.browser_id, rect)),
+        );
+    }
+
+    fn handle_set_browser_layer(&mut self, packet: packets::SetBrowserLayer) {
+        let event = Event::SetBrowserLayer(packet.browser_id, packet.layer);
+        handle_result(self.event_tx.send(event));
+    }
+
+    fn handle_set_zoom(&mut self, packet: packets::SetZoom) {
+        // 0 оставляет текущий масштаб
+        let scale = Some(packet.device_scale)
+            .filter(|&scale| scale > 0.0)
+            .map(|scale| scale.clamp(MIN_DEVICE_SCALE, MAX_DEVICE_SCALE));
+
+        let event = Event::SetZoom(packet.browser_id, packet.zoom_level as f64, scale);
+        handle_result(self.event_tx.send(event));
+    }
+
+    fn handle_set_browser_audio(&mut self, packet: packets::SetBrowserAudio) {
+        let event = Event::SetBrowserAudio(packet.browser_id, packet.volume, packet.muted);
+        handle_result(self.event_tx.send(event));
+    }
+
+    fn handle_set_browser_max_fps(&mut self, packet: packets::SetBrowserMaxFps) {
+        let event = Event::SetBrowserMaxFps(packet.browser_id, packet.max_fps);
+        handle_result(self.event_tx.send(event));
+    }
+
+    fn handle_set_low_power_mode(&mut self, packet: packets::SetLowPowerMode) {
+        let event = Event::SetLowPowerMode(packet.enabled);
+        handle_result(self.event_tx.send(event));
+    }
+
+    fn handle_asset_manifest(&mut self, packet: packets::AssetManifest) {
+        let Some(address) = self.connection_state.addr() else {
+            return;
+        };
+
+        let cache = match AssetCache::new(asset_cache::cache_dir(address), &packet) {
+            Ok(cache) => cache,
+            Err(err) => {
+                log::error!("CEF Network: bad asset manifest: {}", err);
+                self.net_assets_ready(false);
+                return;
+            }
+        };
+
+        handle_result(std::fs::create_dir_all(cache.root()));
+        assets_scheme::set_server_assets_dir(Some(cache.root().to_path_buf()));
+
+        let root = cache.root().to_path_buf();
+        let files = cache.files();
+        let event_tx = self.event_tx.clone();
+
+        self.assets = Some(cache);
+
+        // хэши файлов из кэша считаются не в сетевом потоке
+        std::thread::spawn(move || {
+            let outdated = asset_cache::outdated(&root, &files);
+            handle_result(event_tx.send(Event::RequestAssets(outdated)));
+        });
+    }
+
+    fn handle_asset_chunk(&mut self, packet: packets::AssetChunk) {
+        let Some(cache) = self.assets.as_mut() else {
+            return;
+        };
+
+        if cache.push_chunk(&packet) && cache.is_finished() {
+            let success = !cache.is_failed();
+            self.net_assets_ready(success);
+        }
+    }
+
+    fn handle_take_screenshot(&mut self, packet: packets::TakeScreenshot) {
+        let event = Event::TakeScreenshot(packet.browser_id, packet.request_id);
+        handle_result(self.event_tx.send(event));
+    }
+
+    fn handle_navigate_browser(&mut self, packet: packets::NavigateBrowser) {
+        let event = Event::NavigateBrowser(packet.browser_id, packet.action);
+        handle_result(self.event_tx.send(event));
+    }
+
+    fn handle_execute_javascript(&mut self, packet: packets::ExecuteJavaScript) {
+        let event = Event::ExecuteJavaScript(
+            packet.browser_id,
+            packet.code.to_string(),
+            packet.request_id,
+        );
+        handle_result(self.event_tx.send(event));
+    }
+
     fn net_open_connection(&mut self, address: SocketAddr) {
         let peer = self.socket.connect(address);
         self.connection_state = ConnectionState::Auth(address, Instant::now(), peer);
 
+        // файлы прошлого сервера больше не отдаем
+        assets_scheme::set_server_assets_dir(None);
+
         log::trace!("CEF Network: OpenConnection ({})", address);
         log::trace!(
             "CEF Network: Elapsed since Network module created {:?}",
@@ -353,12 +573,17 @@ impl Network {
         self.socket.send_message(peer, packet);
     }
 
-    fn net_emit_event(&mut self, event: String, args: String) {
+    fn net_emit_event(
+        &mut self, browser_id: u32, event: String, args: String,
+        arguments: Vec<packets::EventValue<'static>>,
+    ) {
         if let ConnectionState::Connected(_address, peer) = self.connection_state {
+            // строка для старых серверов, типизированные значения для `cef_subscribe_typed`
             let emit = packets::EmitEvent {
                 event_name: event.into(),
                 args: Some(args.into()),
-                arguments: Vec::new(),
+                arguments,
+                browser_id: Some(browser_id),
             };
 
             let Ok(packet) = messages::try_into_packet(emit) else {
@@ -370,6 +595,25 @@ impl Network {
         }
     }
 
+    fn net_got(&mut self) {
+        if self.acks.is_empty() {
+            return;
+        }
+
+        let seqs = std::mem::take(&mut self.acks);
+
+        if let ConnectionState::Connected(_address, peer) = self.connection_state {
+            let got = packets::Got { seq: None, seqs };
+
+            let Ok(packet) = messages::try_into_packet(got) else {
+                log::error!("CEF Network: failed to serialize Got");
+                return;
+            };
+
+            self.socket.send_message(peer, packet);
+        }
+    }
+
     fn net_browser_created(&mut self, browser_id: u32, status_code: i32) {
         if let ConnectionState::Connected(_address, peer) = self.connection_state {
             let created = packets::BrowserCreated {
@@ -386,20 +630,252 @@ impl Network {
         }
     }
 
+    fn net_browser_loaded(&mut self, browser_id: u32, url: String, status_code: i32) {
+        if let ConnectionState::Connected(_address, peer) = self.connection_state {
+            let loaded = packets::BrowserLoaded {
+                browser_id,
+                url: url.into(),
+                status_code,
+            };
+
+            let Ok(packet) = messages::try_into_packet(loaded) else {
+                log::error!("CEF Network: failed to serialize BrowserLoaded");
+                return;
+            };
+
+            self.socket.send_message(peer, packet);
+        }
+    }
+
+    fn net_browser_load_error(
+        &mut self, browser_id: u32, error_code: i32, error_text: String, url: String,
+    ) {
+        if let ConnectionState::Connected(_address, peer) = self.connection_state {
+            let error = packets::BrowserLoadError {
+                browser_id,
+                error_code,
+                error_text: error_text.into(),
+                url: url.into(),
+            };
+
+            let Ok(packet) = messages::try_into_packet(error) else {
+                log::error!("CEF Network: failed to serialize BrowserLoadError");
+                return;
+            };
+
+            self.socket.send_message(peer, packet);
+        }
+    }
+
+    fn net_client_info(&mut self, info: crate::app::ClientInfo) {
+        use crate::utils::RenderMode;
+
+        if let ConnectionState::Connected(_address, peer) = self.connection_state {
+            let render_mode = match info.render_mode {
+                RenderMode::DirectX => packets::RenderMode::DIRECTX,
+                RenderMode::Renderware => packets::RenderMode::RENDERWARE,
+                RenderMode::Empty => packets::RenderMode::EMPTY,
+            };
+
+            let client_info = packets::ClientInfo {
+                width: info.width,
+                height: info.height,
+                cef_version: info.cef_version.into(),
+                chromium_version: info.chromium_version.into(),
+                locale: info.locale.into(),
+                render_mode,
+                plugins: info.plugins.into_iter().map(Into::into).collect(),
+            };
+
+            let Ok(packet) = messages::try_into_packet(client_info) else {
+                log::error!("CEF Network: failed to serialize ClientInfo");
+                return;
+            };
+
+            self.socket.send_message(peer, packet);
+        }
+    }
+
+    fn net_browser_destroyed(&mut self, browser_id: u32) {
+        if let ConnectionState::Connected(_address, peer) = self.connection_state {
+            let destroyed = packets::BrowserDestroyed { browser_id };
+
+            let Ok(packet) = messages::try_into_packet(destroyed) else {
+                log::error!("CEF Network: failed to serialize BrowserDestroyed");
+                return;
+            };
+
+            self.socket.send_message(peer, packet);
+        }
+    }
+
+    fn net_renderer_crashed(&mut self, browser_id: u32, status: i32) {
+        if let ConnectionState::Connected(_address, peer) = self.connection_state {
+            let crashed = packets::RendererCrashed { browser_id, status };
+
+            let Ok(packet) = messages::try_into_packet(crashed) else {
+                log::error!("CEF Network: failed to serialize RendererCrashed");
+                return;
+            };
+
+            self.socket.send_message(peer, packet);
+        }
+    }
+
+    fn net_loading_state_changed(
+        &mut self, browser_id: u32, is_loading: bool, can_go_back: bool, can_go_forward: bool,
+    ) {
+        if let ConnectionState::Connected(_address, peer) = self.connection_state {
+            let state = packets::LoadingStateChanged {
+                browser_id,
+                is_loading,
+                can_go_back,
+                can_go_forward,
+            };
+
+            let Ok(packet) = messages::try_into_packet(state) else {
+                log::error!("CEF Network: failed to serialize LoadingStateChanged");
+                return;
+            };
+
+            self.socket.send_message(peer, packet);
+        }
+    }
+
+    fn net_browser_focus_changed(&mut self, browser_id: u32, focused: bool) {
+        if let ConnectionState::Connected(_address, peer) = self.connection_state {
+            let changed = packets::BrowserFocusChanged {
+                browser_id,
+                focused,
+            };
+
+            let Ok(packet) = messages::try_into_packet(changed) else {
+                log::error!("CEF Network: failed to serialize BrowserFocusChanged");
+                return;
+            };
+
+            self.socket.send_message(peer, packet);
+        }
+    }
+
+    fn net_browser_visibility_changed(&mut self, browser_id: u32, hidden: bool) {
+        if let ConnectionState::Connected(_address, peer) = self.connection_state {
+            let changed = packets::BrowserVisibilityChanged { browser_id, hidden };
+
+            let Ok(packet) = messages::try_into_packet(changed) else {
+                log::error!("CEF Network: failed to serialize BrowserVisibilityChanged");
+                return;
+            };
+
+            self.socket.send_message(peer, packet);
+        }
+    }
+
+    fn net_javascript_result(
+        &mut self, browser_id: u32, request_id: u32, success: bool, result: String,
+    ) {
+        if let ConnectionState::Connected(_address, peer) = self.connection_state {
+            let js_result = packets::JavaScriptResult {
+                browser_id,
+                request_id,
+                success,
+                result: result.into(),
+            };
+
+            let Ok(packet) = messages::try_into_packet(js_result) else {
+                log::error!("CEF Network: failed to serialize JavaScriptResult");
+                return;
+            };
+
+            self.socket.send_message(peer, packet);
+        }
+    }
+
+    fn net_request_assets(&mut self, paths: Vec<String>) {
+        let Some(cache) = self.assets.as_mut() else {
+            return;
+        };
+
+        cache.request(&paths);
+
+        if cache.is_finished() {
+            let success = !cache.is_failed();
+            self.net_assets_ready(success);
+            return;
+        }
+
+        log::info!("CEF Network: downloading {} server assets", paths.len());
+
+        if let ConnectionState::Connected(_address, peer) = self.connection_state {
+            let request = packets::RequestAssets {
+                paths: cache.pending().map(Into::into).collect(),
+            };
+
+            let Ok(packet) = messages::try_into_packet(request) else {
+                log::error!("CEF Network: failed to serialize RequestAssets");
+                return;
+            };
+
+            self.socket.send_message(peer, packet);
+        }
+    }
+
+    fn net_assets_ready(&mut self, success: bool) {
+        if let ConnectionState::Connected(_address, peer) = self.connection_state {
+            let Ok(packet) = messages::try_into_packet(packets::AssetsReady { success }) else {
+                log::error!("CEF Network: failed to serialize AssetsReady");
+                return;
+            };
+
+            self.socket.send_message(peer, packet);
+        }
+    }
+
+    /// `None` отправляется одним пустым куском с `total = 0`
+    fn net_screenshot(&mut self, browser_id: u32, request_id: u32, png: Option<Vec<u8>>) {
+        if let ConnectionState::Connected(_address, peer) = self.connection_state {
+            let png = png.unwrap_or_default();
+            let total = png.len().div_ceil(SCREENSHOT_CHUNK_SIZE) as u32;
+
+            let mut chunks: Vec<&[u8]> = png.chunks(SCREENSHOT_CHUNK_SIZE).collect();
+
+            if chunks.is_empty() {
+                chunks.push(&[]);
+            }
+
+            for (index, data) in chunks.into_iter().enumerate() {
+                let chunk = packets::ScreenshotChunk {
+                    browser_id,
+                    request_id,
+                    index: index as u32,
+                    total,
+                    data: data.into(),
+                };
+
+                let Ok(packet) = messages::try_into_packet(chunk) else {
+                    log::error!("CEF Network: failed to serialize ScreenshotChunk");
+                    return;
+                };
+
+                self.socket.send_message(peer, packet);
+            }
+        }
+    }
+
     fn process_network(&mut self) {
         if let Some(server_peer) = self.connection_state.peer() {
             while let Some(event) = self.socket.recv() {
                 match event {
                     SocketEvent::Message(peer, packet) => {
                         if peer == server_peer
-                            && let Err(e) = deserialize_from_slice(&packet)
+                            && let Err(e) = messages::decode(&packet, &self.limits)
                                 .map(|packet| self.handle_packet(packet))
                         {
                             log::trace!("malformed packet from the server: {}", e);
                         }
                     }
 
-                    SocketEvent::Disconnect(peer, _addr) => {
+                    SocketEvent::Disconnect(peer, _addr, _reason) => {
                         if peer == server_peer {
                             log::trace!("CEF Network: Timeout");
                             handle_result(self.event_tx.send(Event::Timeout));
@@ -422,6 +898,14 @@ impl Network {
                     _ => (),
                 }
             }
+
+            self.net_got();
+
+            if let Some(cache) = self.assets.as_mut()
+                && cache.check_timeout()
+            {
+                self.net_assets_ready(false);
+            }
         }
 
         if let ConnectionState::Auth(addr, time, _) = &self.connection_state
@@ -435,8 +919,29 @@ impl Network {
     fn process_event(&mut self, event: Event) {
         match event {
             Event::Connect(addr) => self.net_open_connection(addr),
-            Event::EmitEventOnServer(event, arguments) => self.net_emit_event(event, arguments),
+            Event::EmitEventOnServer(id, event, arguments, values) => {
+                self.net_emit_event(id, event, arguments, values)
+            }
             Event::BrowserCreated(id, code) => self.net_browser_created(id, code),
+            Event::BrowserLoaded(id, url, code) => self.net_browser_loaded(id, url, code),
+            Event::BrowserLoadError(id, code, text, url) => {
+                self.net_browser_load_error(id, code, text, url)
+            }
+            Event::BrowserDestroyed(id) => self.net_browser_destroyed(id),
+            Event::ClientInfo(info) => self.net_client_info(info),
+            Event::RendererCrashed(id, status) => self.net_renderer_crashed(id, status),
+            Event::LoadingStateChanged(id, is_loading, can_go_back, can_go_forward) => {
+                self.net_loading_state_changed(id, is_loading, can_go_back, can_go_forward)
+            }
+            Event::JavaScriptResult(id, request_id, success, result) => {
+                self.net_javascript_result(id, request_id, success, result)
+            }
+            Event::ScreenshotTaken(id, request_id, png) => self.net_screenshot(id, request_id, png),
+            Event::RequestAssets(paths) => self.net_request_assets(paths),
+            Event::BrowserFocusChanged(id, focused) => self.net_browser_focus_changed(id, focused),
+            Event::BrowserVisibilityChanged(id, hidden) => {
+                self.net_browser_visibility_changed(id, hidden)
+            }
             _ => (),
         }
     }
@@ -133,6 +133,9 @@ pub fn render() {
                 manager.update_fps(fps);
             }
 
+            let [width, height] = crate::utils::client_rect();
+            manager.resize(width, height);
+
             manager.do_not_draw(CMenuManager::is_menu_active());
             manager.draw();
         }
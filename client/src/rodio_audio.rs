@@ -11,6 +11,9 @@ use parking_lot::{Mutex, RwLock};
 pub const MAX_DISTANCE: f32 = 50.0;
 pub const REFRENCE_DISTANCE: f32 = 15.0;
 
+/// источник браузера на экране, играет без позиции в мире
+pub const SCREEN_SOURCE: i32 = -1;
+
 #[derive(Copy, Clone)]
 pub struct BrowserAudioSettings {
     pub max_distance: f32,
@@ -53,25 +56,20 @@ impl Stream {
 struct Source {
     sink: Sink,
     #[allow(dead_code)]
-    composer: Arc<BmixerComposer>,
-    sound_controller: SoundController,
+    composer: Option<Arc<BmixerComposer>>,
+    sound_controller: Option<SoundController>, // None у источника на экране
     #[allow(dead_code)]
     queue: Vec<Vec<f32>>,
     #[allow(dead_code)]
     sample_rate: i32,
     muted: bool,
+    volume: f32, // громкость браузера
     pcm_tx: Sender<StreamingCommand>,
 }
 
 impl Source {
-    fn new(handle: &OutputStreamHandle, sample_rate: i32) -> Source {
-        let sink = match Sink::try_new(handle) {
-            Ok(sink) => sink,
-            Err(err) => {
-                log::error!("cannot create a new sink: {:?}", err);
-                std::process::exit(0);
-            }
-        };
+    fn new(handle: &OutputStreamHandle, sample_rate: i32, volume: f32) -> Source {
+        let sink = Self::new_sink(handle);
 
         let (mixer, composer) = ambisonic::bmixer(sample_rate as _);
         let cfg = ambisonic::StereoConfig::default();
@@ -87,12 +85,45 @@ impl Source {
 
         Source {
             sink,
-            composer,
-            sound_controller,
+            composer: Some(composer),
+            sound_controller: Some(sound_controller),
             sample_rate,
             pcm_tx,
             queue: Vec::new(),
             muted: true,
+            volume,
+        }
+    }
+
+    /// без пространственного звука, сразу в sink
+    fn new_screen(handle: &OutputStreamHandle, sample_rate: i32, volume: f32) -> Source {
+        let sink = Self::new_sink(handle);
+
+        let (src, pcm_tx) = StreamingSound::new(sample_rate as u32);
+
+        sink.append(src);
+        sink.set_volume(0.0);
+        sink.play();
+
+        Source {
+            sink,
+            composer: None,
+            sound_controller: None,
+            sample_rate,
+            pcm_tx,
+            queue: Vec::new(),
+            muted: false,
+            volume,
+        }
+    }
+
+    fn new_sink(handle: &OutputStreamHandle) -> Sink {
+        match Sink::try_new(handle) {
+            Ok(sink) => sink,
+            Err(err) => {
+                log::error!("cannot create a new sink: {:?}", err);
+                std::process::exit(0);
+            }
         }
     }
 
@@ -106,13 +137,15 @@ impl Source {
     }
 
     fn set_position(&mut self, position: Point3<f32>) {
-        self.sound_controller
-            .adjust_position([position.x, position.y, position.z]);
+        if let Some(controller) = self.sound_controller.as_mut() {
+            controller.adjust_position([position.x, position.y, position.z]);
+        }
     }
 
     fn set_velocity(&mut self, velocity: Point3<f32>) {
-        self.sound_controller
-            .set_velocity([velocity.x, velocity.y, velocity.z]);
+        if let Some(controller) = self.sound_controller.as_mut() {
+            controller.set_velocity([velocity.x, velocity.y, velocity.z]);
+        }
     }
 }
 
@@ -130,6 +163,15 @@ enum Command {
         object_id: i32,
     },
 
+    ScreenSource {
+        browser: u32,
+    },
+
+    BrowserGain {
+        browser: u32,
+        gain: f32,
+    },
+
     Pcm {
         browser: u32,
         stream_id: i32,
@@ -279,6 +321,15 @@ impl Audio {
             .send(Command::RemoveSource { browser, object_id });
     }
 
+    pub fn add_screen_source(&self, browser: u32) {
+        let _ = self.command_tx.send(Command::ScreenSource { browser });
+    }
+
+    /// громкость отдельного браузера, 0.0 - 1.0
+    pub fn set_browser_gain(&self, browser: u32, gain: f32) {
+        let _ = self.command_tx.send(Command::BrowserGain { browser, gain });
+    }
+
     pub fn set_gain(&self, gain: f32) {
         let _ = self.command_tx.send(Command::Gain(gain));
     }
@@ -372,6 +423,7 @@ struct AudioInner {
     paused: bool,
     gain: f32,
     streams: HashMap<u32, Vec<Stream>>,
+    browser_gain: HashMap<u32, f32>,
 }
 
 impl AudioInner {
@@ -392,6 +444,7 @@ impl AudioInner {
             paused: false,
             gain: 1.0,
             streams: HashMap::new(),
+            browser_gain: HashMap::new(),
         })
     }
 
@@ -445,9 +498,11 @@ impl AudioInner {
 
     fn remove_all_streams(&mut self, browser: u32) {
         self.streams.remove(&browser);
+        self.browser_gain.remove(&browser);
     }
 
     fn add_source(&mut self, browser: u32, object_id: i32) {
+        let volume = self.browser_gain(browser);
         let streams = &mut self.streams;
         let stream_handle = &self.stream_handle;
 
@@ -457,12 +512,53 @@ impl AudioInner {
                     return;
                 }
 
-                let source = Source::new(stream_handle, entry.sample_rate);
+                let source = Source::new(stream_handle, entry.sample_rate, volume);
                 entry.sources.insert(object_id, source);
             });
         }
     }
 
+    fn add_screen_source(&mut self, browser: u32) {
+        let volume = self.browser_gain(browser);
+        let gain = if self.paused { 0.0 } else { self.gain };
+        let streams = &mut self.streams;
+        let stream_handle = &self.stream_handle;
+
+        if let Some(entries) = streams.get_mut(&browser) {
+            entries.iter_mut().for_each(|entry| {
+                if entry.sources.contains_key(&SCREEN_SOURCE) {
+                    return;
+                }
+
+                let source = Source::new_screen(stream_handle, entry.sample_rate, volume);
+                source.sink.set_volume(gain * volume);
+                entry.sources.insert(SCREEN_SOURCE, source);
+            });
+        }
+    }
+
+    fn set_browser_gain(&mut self, browser: u32, volume: f32) {
+        self.browser_gain.insert(browser, volume);
+
+        let gain = if self.paused { 0.0 } else { self.gain };
+
+        if let Some(entries) = self.streams.get_mut(&browser) {
+            entries.iter_mut().for_each(|entry| {
+                entry.sources.values_mut().for_each(|source| {
+                    source.volume = volume;
+
+                    if !source.muted {
+                        source.sink.set_volume(gain * volume);
+                    }
+                });
+            });
+        }
+    }
+
+    fn browser_gain(&self, browser: u32) -> f32 {
+        self.browser_gain.get(&browser).copied().unwrap_or(1.0)
+    }
+
     fn remove_source(&mut self, browser: u32, object_id: i32) {
         if let Some(entries) = self.streams.get_mut(&browser) {
             entries.iter_mut().for_each(|entry| {
@@ -491,7 +587,7 @@ impl AudioInner {
             source.set_velocity(velo);
 
             if source.muted {
-                source.sink.set_volume(gain);
+                source.sink.set_volume(gain * source.volume);
                 source.muted = false;
             }
         });
@@ -523,7 +619,7 @@ impl AudioInner {
             stream.iter_mut().for_each(|stream| {
                 stream.sources.values().for_each(|source| {
                     if !source.muted {
-                        source.sink.set_volume(gain);
+                        source.sink.set_volume(gain * source.volume);
                     }
                 });
             });
@@ -564,6 +660,14 @@ fn audio_thread(mut audio: AudioInner) {
                     audio.add_source(browser, object_id);
                 }
 
+                Command::ScreenSource { browser } => {
+                    audio.add_screen_source(browser);
+                }
+
+                Command::BrowserGain { browser, gain } => {
+                    audio.set_browser_gain(browser, gain);
+                }
+
                 Command::Stream {
                     browser,
                     stream_id,
@@ -7,6 +7,8 @@ use winapi::shared::minwindef::MAX_PATH;
 use winapi::shared::minwindef::{LPARAM, WPARAM};
 use winapi::shared::windef::RECT;
 use winapi::um::shlobj::{CSIDL_MYDOCUMENTS, SHGetFolderPathW};
+use winapi::um::winnls::GetUserDefaultLocaleName;
+use winapi::um::winnt::LOCALE_NAME_MAX_LENGTH;
 use winapi::um::winuser::*;
 
 use cef_sys::cef_event_flags_t::*;
@@ -155,6 +157,19 @@ pub fn documents_path() -> PathBuf {
     path
 }
 
+/// системная локаль в виде `ru-RU`
+pub fn user_locale() -> String {
+    let mut buffer = vec![0; LOCALE_NAME_MAX_LENGTH];
+
+    let len = unsafe { GetUserDefaultLocaleName(buffer.as_mut_ptr(), buffer.len() as i32) };
+
+    if len <= 1 {
+        return String::new();
+    }
+
+    String::from_utf16_lossy(&buffer[0..len as usize - 1])
+}
+
 pub fn game_dir() -> PathBuf {
     if let Some(path) = std::env::args()
         .skip_while(|arg| !arg.contains("--lp"))
@@ -180,6 +195,10 @@ pub fn assets_dir() -> PathBuf {
     cef_dir().join("assets")
 }
 
+pub fn screenshots_dir() -> PathBuf {
+    documents_path().join("screenshots")
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum RenderMode {
     DirectX,
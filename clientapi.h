@@ -33,6 +33,10 @@
         // Подписаться на событие полного создания браузера.
         void cef_on_browser_ready(uint32_t browser, BrowserReadyCallback callback);
         bool cef_ready();
+        // Ограничить частоту кадров браузера, 0 - без ограничения.
+        void cef_set_browser_max_fps(uint32_t browser, int max_fps);
+        // Режим экономии: все браузеры рисуются не чаще 30 раз в секунду.
+        void cef_set_low_power_mode(bool enabled);
         // Подписаться на события от браузера.
         void cef_subscribe(const char *event, EventCallback callback);
         // Попытаться сфокусироваться на браузере. Аналогично паре `cef_input_available` + `cef_focus_browser`,
@@ -3,6 +3,9 @@ use std::convert::TryInto;
 
 pub mod packets;
 pub mod proto;
+pub mod validate;
+
+pub use validate::{DecodeError, Limits, Validate, decode, decode_nested};
 
 #[macro_export]
 macro_rules! impl_into_packet {
@@ -14,6 +17,7 @@ macro_rules! impl_into_packet {
                 Ok($crate::packets::Packet {
                     packet_id: $id,
                     bytes: std::borrow::Cow::Owned(quick_protobuf::serialize_into_vec(&packet)?),
+                    seq: None,
                 })
             }
         }
@@ -18,3 +18,27 @@ impl_into_packet!(RemoveFromObject, PacketId::REMOVE_FROM_OBJECT);
 impl_into_packet!(ToggleDevTools, PacketId::TOGGLE_DEV_TOOLS);
 impl_into_packet!(SetAudioSettings, PacketId::SET_AUDIO_SETTINGS);
 impl_into_packet!(LoadUrl<'a>, PacketId::LOAD_URL);
+impl_into_packet!(ExecuteJavaScript<'a>, PacketId::EXECUTE_JAVASCRIPT);
+impl_into_packet!(JavaScriptResult<'a>, PacketId::JAVASCRIPT_RESULT);
+impl_into_packet!(BrowserLoaded<'a>, PacketId::BROWSER_LOADED);
+impl_into_packet!(BrowserLoadError<'a>, PacketId::BROWSER_LOAD_ERROR);
+impl_into_packet!(BrowserDestroyed, PacketId::BROWSER_DESTROYED);
+impl_into_packet!(RendererCrashed, PacketId::RENDERER_CRASHED);
+impl_into_packet!(ClientInfo<'a>, PacketId::CLIENT_INFO);
+impl_into_packet!(Batch<'a>, PacketId::BATCH);
+impl_into_packet!(NavigateBrowser, PacketId::NAVIGATE_BROWSER);
+impl_into_packet!(LoadingStateChanged, PacketId::LOADING_STATE_CHANGED);
+impl_into_packet!(SetBrowserRect, PacketId::SET_BROWSER_RECT);
+impl_into_packet!(SetBrowserLayer, PacketId::SET_BROWSER_LAYER);
+impl_into_packet!(SetZoom, PacketId::SET_ZOOM);
+impl_into_packet!(SetBrowserAudio, PacketId::SET_BROWSER_AUDIO);
+impl_into_packet!(SetBrowserMaxFps, PacketId::SET_BROWSER_MAX_FPS);
+impl_into_packet!(SetLowPowerMode, PacketId::SET_LOW_POWER_MODE);
+impl_into_packet!(TakeScreenshot, PacketId::TAKE_SCREENSHOT);
+impl_into_packet!(ScreenshotChunk<'a>, PacketId::SCREENSHOT_CHUNK);
+impl_into_packet!(AssetManifest<'a>, PacketId::ASSET_MANIFEST);
+impl_into_packet!(RequestAssets<'a>, PacketId::REQUEST_ASSETS);
+impl_into_packet!(AssetChunk<'a>, PacketId::ASSET_CHUNK);
+impl_into_packet!(AssetsReady, PacketId::ASSETS_READY);
+impl_into_packet!(BrowserFocusChanged, PacketId::BROWSER_FOCUS_CHANGED);
+impl_into_packet!(BrowserVisibilityChanged, PacketId::BROWSER_VISIBILITY_CHANGED);
@@ -30,9 +30,33 @@ pub enum PacketId {
     TOGGLE_DEV_TOOLS = 14,
     SET_AUDIO_SETTINGS = 15,
     LOAD_URL = 16,
+    EXECUTE_JAVASCRIPT = 17,
+    BATCH = 24,
+    NAVIGATE_BROWSER = 25,
+    SET_BROWSER_RECT = 27,
+    SET_BROWSER_LAYER = 28,
+    SET_ZOOM = 29,
+    SET_BROWSER_AUDIO = 30,
+    SET_BROWSER_MAX_FPS = 31,
+    SET_LOW_POWER_MODE = 32,
+    TAKE_SCREENSHOT = 33,
+    ASSET_MANIFEST = 35,
+    ASSET_CHUNK = 36,
     EMIT_EVENT = 8,
     BROWSER_CREATED = 9,
     GOT = 10,
+    JAVASCRIPT_RESULT = 18,
+    BROWSER_LOADED = 19,
+    BROWSER_LOAD_ERROR = 20,
+    BROWSER_DESTROYED = 21,
+    RENDERER_CRASHED = 22,
+    CLIENT_INFO = 23,
+    LOADING_STATE_CHANGED = 26,
+    SCREENSHOT_CHUNK = 34,
+    REQUEST_ASSETS = 37,
+    ASSETS_READY = 38,
+    BROWSER_FOCUS_CHANGED = 39,
+    BROWSER_VISIBILITY_CHANGED = 40,
 }
 
 impl Default for PacketId {
@@ -58,9 +82,33 @@ impl From<i32> for PacketId {
             14 => PacketId::TOGGLE_DEV_TOOLS,
             15 => PacketId::SET_AUDIO_SETTINGS,
             16 => PacketId::LOAD_URL,
+            17 => PacketId::EXECUTE_JAVASCRIPT,
+            24 => PacketId::BATCH,
+            25 => PacketId::NAVIGATE_BROWSER,
+            27 => PacketId::SET_BROWSER_RECT,
+            28 => PacketId::SET_BROWSER_LAYER,
+            29 => PacketId::SET_ZOOM,
+            30 => PacketId::SET_BROWSER_AUDIO,
+            31 => PacketId::SET_BROWSER_MAX_FPS,
+            32 => PacketId::SET_LOW_POWER_MODE,
+            33 => PacketId::TAKE_SCREENSHOT,
+            35 => PacketId::ASSET_MANIFEST,
+            36 => PacketId::ASSET_CHUNK,
             8 => PacketId::EMIT_EVENT,
             9 => PacketId::BROWSER_CREATED,
             10 => PacketId::GOT,
+            18 => PacketId::JAVASCRIPT_RESULT,
+            19 => PacketId::BROWSER_LOADED,
+            20 => PacketId::BROWSER_LOAD_ERROR,
+            21 => PacketId::BROWSER_DESTROYED,
+            22 => PacketId::RENDERER_CRASHED,
+            23 => PacketId::CLIENT_INFO,
+            26 => PacketId::LOADING_STATE_CHANGED,
+            34 => PacketId::SCREENSHOT_CHUNK,
+            37 => PacketId::REQUEST_ASSETS,
+            38 => PacketId::ASSETS_READY,
+            39 => PacketId::BROWSER_FOCUS_CHANGED,
+            40 => PacketId::BROWSER_VISIBILITY_CHANGED,
             _ => Self::default(),
         }
     }
@@ -83,9 +131,162 @@ impl<'a> From<&'a str> for PacketId {
             "TOGGLE_DEV_TOOLS" => PacketId::TOGGLE_DEV_TOOLS,
             "SET_AUDIO_SETTINGS" => PacketId::SET_AUDIO_SETTINGS,
             "LOAD_URL" => PacketId::LOAD_URL,
+            "EXECUTE_JAVASCRIPT" => PacketId::EXECUTE_JAVASCRIPT,
+            "BATCH" => PacketId::BATCH,
+            "NAVIGATE_BROWSER" => PacketId::NAVIGATE_BROWSER,
+            "SET_BROWSER_RECT" => PacketId::SET_BROWSER_RECT,
+            "SET_BROWSER_LAYER" => PacketId::SET_BROWSER_LAYER,
+            "SET_ZOOM" => PacketId::SET_ZOOM,
+            "SET_BROWSER_AUDIO" => PacketId::SET_BROWSER_AUDIO,
+            "SET_BROWSER_MAX_FPS" => PacketId::SET_BROWSER_MAX_FPS,
+            "SET_LOW_POWER_MODE" => PacketId::SET_LOW_POWER_MODE,
+            "TAKE_SCREENSHOT" => PacketId::TAKE_SCREENSHOT,
+            "ASSET_MANIFEST" => PacketId::ASSET_MANIFEST,
+            "ASSET_CHUNK" => PacketId::ASSET_CHUNK,
             "EMIT_EVENT" => PacketId::EMIT_EVENT,
             "BROWSER_CREATED" => PacketId::BROWSER_CREATED,
             "GOT" => PacketId::GOT,
+            "JAVASCRIPT_RESULT" => PacketId::JAVASCRIPT_RESULT,
+            "BROWSER_LOADED" => PacketId::BROWSER_LOADED,
+            "BROWSER_LOAD_ERROR" => PacketId::BROWSER_LOAD_ERROR,
+            "BROWSER_DESTROYED" => PacketId::BROWSER_DESTROYED,
+            "RENDERER_CRASHED" => PacketId::RENDERER_CRASHED,
+            "CLIENT_INFO" => PacketId::CLIENT_INFO,
+            "LOADING_STATE_CHANGED" => PacketId::LOADING_STATE_CHANGED,
+            "SCREENSHOT_CHUNK" => PacketId::SCREENSHOT_CHUNK,
+            "REQUEST_ASSETS" => PacketId::REQUEST_ASSETS,
+            "ASSETS_READY" => PacketId::ASSETS_READY,
+            "BROWSER_FOCUS_CHANGED" => PacketId::BROWSER_FOCUS_CHANGED,
+            "BROWSER_VISIBILITY_CHANGED" => PacketId::BROWSER_VISIBILITY_CHANGED,
+            _ => Self::default(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RenderMode {
+    DIRECTX = 0,
+    RENDERWARE = 1,
+    EMPTY = 2,
+}
+
+impl Default for RenderMode {
+    fn default() -> Self {
+        RenderMode::DIRECTX
+    }
+}
+
+impl From<i32> for RenderMode {
+    fn from(i: i32) -> Self {
+        match i {
+            0 => RenderMode::DIRECTX,
+            1 => RenderMode::RENDERWARE,
+            2 => RenderMode::EMPTY,
+            _ => Self::default(),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for RenderMode {
+    fn from(s: &'a str) -> Self {
+        match s {
+            "DIRECTX" => RenderMode::DIRECTX,
+            "RENDERWARE" => RenderMode::RENDERWARE,
+            "EMPTY" => RenderMode::EMPTY,
+            _ => Self::default(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum NavigationAction {
+    RELOAD = 0,
+    RELOAD_IGNORE_CACHE = 1,
+    GO_BACK = 2,
+    GO_FORWARD = 3,
+    STOP = 4,
+}
+
+impl Default for NavigationAction {
+    fn default() -> Self {
+        NavigationAction::RELOAD
+    }
+}
+
+impl From<i32> for NavigationAction {
+    fn from(i: i32) -> Self {
+        match i {
+            0 => NavigationAction::RELOAD,
+            1 => NavigationAction::RELOAD_IGNORE_CACHE,
+            2 => NavigationAction::GO_BACK,
+            3 => NavigationAction::GO_FORWARD,
+            4 => NavigationAction::STOP,
+            _ => Self::default(),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for NavigationAction {
+    fn from(s: &'a str) -> Self {
+        match s {
+            "RELOAD" => NavigationAction::RELOAD,
+            "RELOAD_IGNORE_CACHE" => NavigationAction::RELOAD_IGNORE_CACHE,
+            "GO_BACK" => NavigationAction::GO_BACK,
+            "GO_FORWARD" => NavigationAction::GO_FORWARD,
+            "STOP" => NavigationAction::STOP,
+            _ => Self::default(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Anchor {
+    TOP_LEFT = 0,
+    TOP = 1,
+    TOP_RIGHT = 2,
+    LEFT = 3,
+    CENTER = 4,
+    RIGHT = 5,
+    BOTTOM_LEFT = 6,
+    BOTTOM = 7,
+    BOTTOM_RIGHT = 8,
+}
+
+impl Default for Anchor {
+    fn default() -> Self {
+        Anchor::TOP_LEFT
+    }
+}
+
+impl From<i32> for Anchor {
+    fn from(i: i32) -> Self {
+        match i {
+            0 => Anchor::TOP_LEFT,
+            1 => Anchor::TOP,
+            2 => Anchor::TOP_RIGHT,
+            3 => Anchor::LEFT,
+            4 => Anchor::CENTER,
+            5 => Anchor::RIGHT,
+            6 => Anchor::BOTTOM_LEFT,
+            7 => Anchor::BOTTOM,
+            8 => Anchor::BOTTOM_RIGHT,
+            _ => Self::default(),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Anchor {
+    fn from(s: &'a str) -> Self {
+        match s {
+            "TOP_LEFT" => Anchor::TOP_LEFT,
+            "TOP" => Anchor::TOP,
+            "TOP_RIGHT" => Anchor::TOP_RIGHT,
+            "LEFT" => Anchor::LEFT,
+            "CENTER" => Anchor::CENTER,
+            "RIGHT" => Anchor::RIGHT,
+            "BOTTOM_LEFT" => Anchor::BOTTOM_LEFT,
+            "BOTTOM" => Anchor::BOTTOM,
+            "BOTTOM_RIGHT" => Anchor::BOTTOM_RIGHT,
             _ => Self::default(),
         }
     }
@@ -95,6 +296,7 @@ impl<'a> From<&'a str> for PacketId {
 pub struct Packet<'a> {
     pub packet_id: PacketId,
     pub bytes: Cow<'a, [u8]>,
+    pub seq: Option<u32>,
 }
 
 impl<'a> MessageRead<'a> for Packet<'a> {
@@ -104,6 +306,7 @@ impl<'a> MessageRead<'a> for Packet<'a> {
             match r.next_tag(bytes) {
                 Ok(8) => msg.packet_id = r.read_enum(bytes)?,
                 Ok(18) => msg.bytes = r.read_bytes(bytes).map(Cow::Borrowed)?,
+                Ok(24) => msg.seq = Some(r.read_uint32(bytes)?),
                 Ok(t) => { r.read_unknown(bytes, t)?; }
                 Err(e) => return Err(e),
             }
@@ -117,11 +320,13 @@ impl<'a> MessageWrite for Packet<'a> {
         0
         + 1 + sizeof_varint(*(&self.packet_id) as u64)
         + 1 + sizeof_len((&self.bytes).len())
+        + self.seq.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
     }
 
     fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
         w.write_with_tag(8, |w| w.write_enum(*&self.packet_id as i32))?;
         w.write_with_tag(18, |w| w.write_bytes(&**&self.bytes))?;
+        if let Some(ref s) = self.seq { w.write_with_tag(24, |w| w.write_uint32(*s))?; }
         Ok(())
     }
 }
@@ -306,6 +511,7 @@ pub struct EmitEvent<'a> {
     pub event_name: Cow<'a, str>,
     pub args: Option<Cow<'a, str>>,
     pub arguments: Vec<EventValue<'a>>,
+    pub browser_id: Option<u32>,
 }
 
 impl<'a> MessageRead<'a> for EmitEvent<'a> {
@@ -316,6 +522,7 @@ impl<'a> MessageRead<'a> for EmitEvent<'a> {
                 Ok(10) => msg.event_name = r.read_string(bytes).map(Cow::Borrowed)?,
                 Ok(18) => msg.args = Some(r.read_string(bytes).map(Cow::Borrowed)?),
                 Ok(26) => msg.arguments.push(r.read_message::<EventValue>(bytes)?),
+                Ok(32) => msg.browser_id = Some(r.read_uint32(bytes)?),
                 Ok(t) => { r.read_unknown(bytes, t)?; }
                 Err(e) => return Err(e),
             }
@@ -330,12 +537,14 @@ impl<'a> MessageWrite for EmitEvent<'a> {
         + 1 + sizeof_len((&self.event_name).len())
         + self.args.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
         + self.arguments.iter().map(|s| 1 + sizeof_len((s).get_size())).sum::<usize>()
+        + self.browser_id.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
     }
 
     fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
         w.write_with_tag(10, |w| w.write_string(&**&self.event_name))?;
         if let Some(ref s) = self.args { w.write_with_tag(18, |w| w.write_string(&**s))?; }
         for s in &self.arguments { w.write_with_tag(26, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.browser_id { w.write_with_tag(32, |w| w.write_uint32(*s))?; }
         Ok(())
     }
 }
@@ -485,16 +694,39 @@ impl MessageWrite for BrowserCreated {
 }
 
 #[derive(Debug, Default, PartialEq, Clone)]
-pub struct Got { }
+pub struct Got {
+    pub seq: Option<u32>,
+    pub seqs: Vec<u32>,
+}
 
 impl<'a> MessageRead<'a> for Got {
-    fn from_reader(r: &mut BytesReader, _: &[u8]) -> Result<Self> {
-        r.read_to_end();
-        Ok(Self::default())
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.seq = Some(r.read_uint32(bytes)?),
+                Ok(16) => msg.seqs.push(r.read_uint32(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
     }
 }
 
-impl MessageWrite for Got { }
+impl MessageWrite for Got {
+    fn get_size(&self) -> usize {
+        0
+        + self.seq.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.seqs.iter().map(|s| 1 + sizeof_varint(*(s) as u64)).sum::<usize>()
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.seq { w.write_with_tag(8, |w| w.write_uint32(*s))?; }
+        for s in &self.seqs { w.write_with_tag(16, |w| w.write_uint32(*s))?; }
+        Ok(())
+    }
+}
 
 #[derive(Debug, Default, PartialEq, Clone)]
 pub struct OpenConnection { }
@@ -730,3 +962,958 @@ impl<'a> MessageWrite for LoadUrl<'a> {
     }
 }
 
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ExecuteJavaScript<'a> {
+    pub browser_id: u32,
+    pub code: Cow<'a, str>,
+    pub request_id: Option<u32>,
+}
+
+impl<'a> MessageRead<'a> for ExecuteJavaScript<'a> {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.browser_id = r.read_uint32(bytes)?,
+                Ok(18) => msg.code = r.read_string(bytes).map(Cow::Borrowed)?,
+                Ok(24) => msg.request_id = Some(r.read_uint32(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl<'a> MessageWrite for ExecuteJavaScript<'a> {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.browser_id) as u64)
+        + 1 + sizeof_len((&self.code).len())
+        + self.request_id.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_uint32(*&self.browser_id))?;
+        w.write_with_tag(18, |w| w.write_string(&**&self.code))?;
+        if let Some(ref s) = self.request_id { w.write_with_tag(24, |w| w.write_uint32(*s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct JavaScriptResult<'a> {
+    pub browser_id: u32,
+    pub request_id: u32,
+    pub success: bool,
+    pub result: Cow<'a, str>,
+}
+
+impl<'a> MessageRead<'a> for JavaScriptResult<'a> {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.browser_id = r.read_uint32(bytes)?,
+                Ok(16) => msg.request_id = r.read_uint32(bytes)?,
+                Ok(24) => msg.success = r.read_bool(bytes)?,
+                Ok(34) => msg.result = r.read_string(bytes).map(Cow::Borrowed)?,
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl<'a> MessageWrite for JavaScriptResult<'a> {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.browser_id) as u64)
+        + 1 + sizeof_varint(*(&self.request_id) as u64)
+        + 1 + sizeof_varint(*(&self.success) as u64)
+        + 1 + sizeof_len((&self.result).len())
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_uint32(*&self.browser_id))?;
+        w.write_with_tag(16, |w| w.write_uint32(*&self.request_id))?;
+        w.write_with_tag(24, |w| w.write_bool(*&self.success))?;
+        w.write_with_tag(34, |w| w.write_string(&**&self.result))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct BrowserLoaded<'a> {
+    pub browser_id: u32,
+    pub url: Cow<'a, str>,
+    pub status_code: i32,
+}
+
+impl<'a> MessageRead<'a> for BrowserLoaded<'a> {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.browser_id = r.read_uint32(bytes)?,
+                Ok(18) => msg.url = r.read_string(bytes).map(Cow::Borrowed)?,
+                Ok(24) => msg.status_code = r.read_int32(bytes)?,
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl<'a> MessageWrite for BrowserLoaded<'a> {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.browser_id) as u64)
+        + 1 + sizeof_len((&self.url).len())
+        + 1 + sizeof_varint(*(&self.status_code) as u64)
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_uint32(*&self.browser_id))?;
+        w.write_with_tag(18, |w| w.write_string(&**&self.url))?;
+        w.write_with_tag(24, |w| w.write_int32(*&self.status_code))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct BrowserLoadError<'a> {
+    pub browser_id: u32,
+    pub error_code: i32,
+    pub error_text: Cow<'a, str>,
+    pub url: Cow<'a, str>,
+}
+
+impl<'a> MessageRead<'a> for BrowserLoadError<'a> {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.browser_id = r.read_uint32(bytes)?,
+                Ok(16) => msg.error_code = r.read_int32(bytes)?,
+                Ok(26) => msg.error_text = r.read_string(bytes).map(Cow::Borrowed)?,
+                Ok(34) => msg.url = r.read_string(bytes).map(Cow::Borrowed)?,
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl<'a> MessageWrite for BrowserLoadError<'a> {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.browser_id) as u64)
+        + 1 + sizeof_varint(*(&self.error_code) as u64)
+        + 1 + sizeof_len((&self.error_text).len())
+        + 1 + sizeof_len((&self.url).len())
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_uint32(*&self.browser_id))?;
+        w.write_with_tag(16, |w| w.write_int32(*&self.error_code))?;
+        w.write_with_tag(26, |w| w.write_string(&**&self.error_text))?;
+        w.write_with_tag(34, |w| w.write_string(&**&self.url))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct BrowserDestroyed {
+    pub browser_id: u32,
+}
+
+impl<'a> MessageRead<'a> for BrowserDestroyed {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.browser_id = r.read_uint32(bytes)?,
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for BrowserDestroyed {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.browser_id) as u64)
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_uint32(*&self.browser_id))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct RendererCrashed {
+    pub browser_id: u32,
+    pub status: i32,
+}
+
+impl<'a> MessageRead<'a> for RendererCrashed {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.browser_id = r.read_uint32(bytes)?,
+                Ok(16) => msg.status = r.read_int32(bytes)?,
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for RendererCrashed {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.browser_id) as u64)
+        + 1 + sizeof_varint(*(&self.status) as u64)
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_uint32(*&self.browser_id))?;
+        w.write_with_tag(16, |w| w.write_int32(*&self.status))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ClientInfo<'a> {
+    pub width: u32,
+    pub height: u32,
+    pub cef_version: Cow<'a, str>,
+    pub chromium_version: Cow<'a, str>,
+    pub locale: Cow<'a, str>,
+    pub render_mode: RenderMode,
+    pub plugins: Vec<Cow<'a, str>>,
+}
+
+impl<'a> MessageRead<'a> for ClientInfo<'a> {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.width = r.read_uint32(bytes)?,
+                Ok(16) => msg.height = r.read_uint32(bytes)?,
+                Ok(26) => msg.cef_version = r.read_string(bytes).map(Cow::Borrowed)?,
+                Ok(34) => msg.chromium_version = r.read_string(bytes).map(Cow::Borrowed)?,
+                Ok(42) => msg.locale = r.read_string(bytes).map(Cow::Borrowed)?,
+                Ok(48) => msg.render_mode = r.read_enum(bytes)?,
+                Ok(58) => msg.plugins.push(r.read_string(bytes).map(Cow::Borrowed)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl<'a> MessageWrite for ClientInfo<'a> {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.width) as u64)
+        + 1 + sizeof_varint(*(&self.height) as u64)
+        + 1 + sizeof_len((&self.cef_version).len())
+        + 1 + sizeof_len((&self.chromium_version).len())
+        + 1 + sizeof_len((&self.locale).len())
+        + 1 + sizeof_varint(*(&self.render_mode) as u64)
+        + self.plugins.iter().map(|s| 1 + sizeof_len((s).len())).sum::<usize>()
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_uint32(*&self.width))?;
+        w.write_with_tag(16, |w| w.write_uint32(*&self.height))?;
+        w.write_with_tag(26, |w| w.write_string(&**&self.cef_version))?;
+        w.write_with_tag(34, |w| w.write_string(&**&self.chromium_version))?;
+        w.write_with_tag(42, |w| w.write_string(&**&self.locale))?;
+        w.write_with_tag(48, |w| w.write_enum(*&self.render_mode as i32))?;
+        for s in &self.plugins { w.write_with_tag(58, |w| w.write_string(&**s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Batch<'a> {
+    pub packets: Vec<Packet<'a>>,
+}
+
+impl<'a> MessageRead<'a> for Batch<'a> {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.packets.push(r.read_message::<Packet>(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl<'a> MessageWrite for Batch<'a> {
+    fn get_size(&self) -> usize {
+        0
+        + self.packets.iter().map(|s| 1 + sizeof_len((s).get_size())).sum::<usize>()
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        for s in &self.packets { w.write_with_tag(10, |w| w.write_message(s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct NavigateBrowser {
+    pub browser_id: u32,
+    pub action: NavigationAction,
+}
+
+impl<'a> MessageRead<'a> for NavigateBrowser {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.browser_id = r.read_uint32(bytes)?,
+                Ok(16) => msg.action = r.read_enum(bytes)?,
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for NavigateBrowser {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.browser_id) as u64)
+        + 1 + sizeof_varint(*(&self.action) as u64)
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_uint32(*&self.browser_id))?;
+        w.write_with_tag(16, |w| w.write_enum(*&self.action as i32))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct LoadingStateChanged {
+    pub browser_id: u32,
+    pub is_loading: bool,
+    pub can_go_back: bool,
+    pub can_go_forward: bool,
+}
+
+impl<'a> MessageRead<'a> for LoadingStateChanged {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.browser_id = r.read_uint32(bytes)?,
+                Ok(16) => msg.is_loading = r.read_bool(bytes)?,
+                Ok(24) => msg.can_go_back = r.read_bool(bytes)?,
+                Ok(32) => msg.can_go_forward = r.read_bool(bytes)?,
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for LoadingStateChanged {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.browser_id) as u64)
+        + 1 + sizeof_varint(*(&self.is_loading) as u64)
+        + 1 + sizeof_varint(*(&self.can_go_back) as u64)
+        + 1 + sizeof_varint(*(&self.can_go_forward) as u64)
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_uint32(*&self.browser_id))?;
+        w.write_with_tag(16, |w| w.write_bool(*&self.is_loading))?;
+        w.write_with_tag(24, |w| w.write_bool(*&self.can_go_back))?;
+        w.write_with_tag(32, |w| w.write_bool(*&self.can_go_forward))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct SetBrowserRect {
+    pub browser_id: u32,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub anchor: Anchor,
+    pub percent: bool,
+}
+
+impl<'a> MessageRead<'a> for SetBrowserRect {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.browser_id = r.read_uint32(bytes)?,
+                Ok(21) => msg.x = r.read_float(bytes)?,
+                Ok(29) => msg.y = r.read_float(bytes)?,
+                Ok(37) => msg.width = r.read_float(bytes)?,
+                Ok(45) => msg.height = r.read_float(bytes)?,
+                Ok(48) => msg.anchor = r.read_enum(bytes)?,
+                Ok(56) => msg.percent = r.read_bool(bytes)?,
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for SetBrowserRect {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.browser_id) as u64)
+        + 1 + 4
+        + 1 + 4
+        + 1 + 4
+        + 1 + 4
+        + 1 + sizeof_varint(*(&self.anchor) as u64)
+        + 1 + sizeof_varint(*(&self.percent) as u64)
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_uint32(*&self.browser_id))?;
+        w.write_with_tag(21, |w| w.write_float(*&self.x))?;
+        w.write_with_tag(29, |w| w.write_float(*&self.y))?;
+        w.write_with_tag(37, |w| w.write_float(*&self.width))?;
+        w.write_with_tag(45, |w| w.write_float(*&self.height))?;
+        w.write_with_tag(48, |w| w.write_enum(*&self.anchor as i32))?;
+        w.write_with_tag(56, |w| w.write_bool(*&self.percent))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct SetBrowserLayer {
+    pub browser_id: u32,
+    pub layer: i32,
+}
+
+impl<'a> MessageRead<'a> for SetBrowserLayer {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.browser_id = r.read_uint32(bytes)?,
+                Ok(16) => msg.layer = r.read_int32(bytes)?,
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for SetBrowserLayer {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.browser_id) as u64)
+        + 1 + sizeof_varint(*(&self.layer) as u64)
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_uint32(*&self.browser_id))?;
+        w.write_with_tag(16, |w| w.write_int32(*&self.layer))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct SetZoom {
+    pub browser_id: u32,
+    pub zoom_level: f32,
+    pub device_scale: f32,
+}
+
+impl<'a> MessageRead<'a> for SetZoom {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.browser_id = r.read_uint32(bytes)?,
+                Ok(21) => msg.zoom_level = r.read_float(bytes)?,
+                Ok(29) => msg.device_scale = r.read_float(bytes)?,
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for SetZoom {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.browser_id) as u64)
+        + 1 + 4
+        + 1 + 4
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_uint32(*&self.browser_id))?;
+        w.write_with_tag(21, |w| w.write_float(*&self.zoom_level))?;
+        w.write_with_tag(29, |w| w.write_float(*&self.device_scale))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct SetBrowserAudio {
+    pub browser_id: u32,
+    pub volume: Option<f32>,
+    pub muted: Option<bool>,
+}
+
+impl<'a> MessageRead<'a> for SetBrowserAudio {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.browser_id = r.read_uint32(bytes)?,
+                Ok(21) => msg.volume = Some(r.read_float(bytes)?),
+                Ok(24) => msg.muted = Some(r.read_bool(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for SetBrowserAudio {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.browser_id) as u64)
+        + self.volume.as_ref().map_or(0, |_| 1 + 4)
+        + self.muted.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_uint32(*&self.browser_id))?;
+        if let Some(ref s) = self.volume { w.write_with_tag(21, |w| w.write_float(*s))?; }
+        if let Some(ref s) = self.muted { w.write_with_tag(24, |w| w.write_bool(*s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct SetBrowserMaxFps {
+    pub browser_id: u32,
+    pub max_fps: i32,
+}
+
+impl<'a> MessageRead<'a> for SetBrowserMaxFps {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.browser_id = r.read_uint32(bytes)?,
+                Ok(16) => msg.max_fps = r.read_int32(bytes)?,
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for SetBrowserMaxFps {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.browser_id) as u64)
+        + 1 + sizeof_varint(*(&self.max_fps) as u64)
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_uint32(*&self.browser_id))?;
+        w.write_with_tag(16, |w| w.write_int32(*&self.max_fps))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct SetLowPowerMode {
+    pub enabled: bool,
+}
+
+impl<'a> MessageRead<'a> for SetLowPowerMode {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.enabled = r.read_bool(bytes)?,
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for SetLowPowerMode {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.enabled) as u64)
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_bool(*&self.enabled))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct TakeScreenshot {
+    pub browser_id: u32,
+    pub request_id: Option<u32>,
+}
+
+impl<'a> MessageRead<'a> for TakeScreenshot {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.browser_id = r.read_uint32(bytes)?,
+                Ok(16) => msg.request_id = Some(r.read_uint32(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for TakeScreenshot {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.browser_id) as u64)
+        + self.request_id.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_uint32(*&self.browser_id))?;
+        if let Some(ref s) = self.request_id { w.write_with_tag(16, |w| w.write_uint32(*s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ScreenshotChunk<'a> {
+    pub browser_id: u32,
+    pub request_id: u32,
+    pub index: u32,
+    pub total: u32,
+    pub data: Cow<'a, [u8]>,
+}
+
+impl<'a> MessageRead<'a> for ScreenshotChunk<'a> {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.browser_id = r.read_uint32(bytes)?,
+                Ok(16) => msg.request_id = r.read_uint32(bytes)?,
+                Ok(24) => msg.index = r.read_uint32(bytes)?,
+                Ok(32) => msg.total = r.read_uint32(bytes)?,
+                Ok(42) => msg.data = r.read_bytes(bytes).map(Cow::Borrowed)?,
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl<'a> MessageWrite for ScreenshotChunk<'a> {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.browser_id) as u64)
+        + 1 + sizeof_varint(*(&self.request_id) as u64)
+        + 1 + sizeof_varint(*(&self.index) as u64)
+        + 1 + sizeof_varint(*(&self.total) as u64)
+        + 1 + sizeof_len((&self.data).len())
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_uint32(*&self.browser_id))?;
+        w.write_with_tag(16, |w| w.write_uint32(*&self.request_id))?;
+        w.write_with_tag(24, |w| w.write_uint32(*&self.index))?;
+        w.write_with_tag(32, |w| w.write_uint32(*&self.total))?;
+        w.write_with_tag(42, |w| w.write_bytes(&**&self.data))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct AssetFile<'a> {
+    pub path: Cow<'a, str>,
+    pub size: u64,
+    pub hash: Cow<'a, [u8]>,
+}
+
+impl<'a> MessageRead<'a> for AssetFile<'a> {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.path = r.read_string(bytes).map(Cow::Borrowed)?,
+                Ok(16) => msg.size = r.read_uint64(bytes)?,
+                Ok(26) => msg.hash = r.read_bytes(bytes).map(Cow::Borrowed)?,
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl<'a> MessageWrite for AssetFile<'a> {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_len((&self.path).len())
+        + 1 + sizeof_varint(*(&self.size) as u64)
+        + 1 + sizeof_len((&self.hash).len())
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(10, |w| w.write_string(&**&self.path))?;
+        w.write_with_tag(16, |w| w.write_uint64(*&self.size))?;
+        w.write_with_tag(26, |w| w.write_bytes(&**&self.hash))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct AssetManifest<'a> {
+    pub files: Vec<AssetFile<'a>>,
+}
+
+impl<'a> MessageRead<'a> for AssetManifest<'a> {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.files.push(r.read_message::<AssetFile>(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl<'a> MessageWrite for AssetManifest<'a> {
+    fn get_size(&self) -> usize {
+        0
+        + self.files.iter().map(|s| 1 + sizeof_len((s).get_size())).sum::<usize>()
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        for s in &self.files { w.write_with_tag(10, |w| w.write_message(s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct RequestAssets<'a> {
+    pub paths: Vec<Cow<'a, str>>,
+}
+
+impl<'a> MessageRead<'a> for RequestAssets<'a> {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.paths.push(r.read_string(bytes).map(Cow::Borrowed)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl<'a> MessageWrite for RequestAssets<'a> {
+    fn get_size(&self) -> usize {
+        0
+        + self.paths.iter().map(|s| 1 + sizeof_len((s).len())).sum::<usize>()
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        for s in &self.paths { w.write_with_tag(10, |w| w.write_string(&**s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct AssetChunk<'a> {
+    pub path: Cow<'a, str>,
+    pub index: u32,
+    pub total: u32,
+    pub data: Cow<'a, [u8]>,
+}
+
+impl<'a> MessageRead<'a> for AssetChunk<'a> {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.path = r.read_string(bytes).map(Cow::Borrowed)?,
+                Ok(16) => msg.index = r.read_uint32(bytes)?,
+                Ok(24) => msg.total = r.read_uint32(bytes)?,
+                Ok(34) => msg.data = r.read_bytes(bytes).map(Cow::Borrowed)?,
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl<'a> MessageWrite for AssetChunk<'a> {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_len((&self.path).len())
+        + 1 + sizeof_varint(*(&self.index) as u64)
+        + 1 + sizeof_varint(*(&self.total) as u64)
+        + 1 + sizeof_len((&self.data).len())
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(10, |w| w.write_string(&**&self.path))?;
+        w.write_with_tag(16, |w| w.write_uint32(*&self.index))?;
+        w.write_with_tag(24, |w| w.write_uint32(*&self.total))?;
+        w.write_with_tag(34, |w| w.write_bytes(&**&self.data))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct AssetsReady {
+    pub success: bool,
+}
+
+impl<'a> MessageRead<'a> for AssetsReady {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.success = r.read_bool(bytes)?,
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for AssetsReady {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.success) as u64)
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_bool(*&self.success))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct BrowserFocusChanged {
+    pub browser_id: u32,
+    pub focused: bool,
+}
+
+impl<'a> MessageRead<'a> for BrowserFocusChanged {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.browser_id = r.read_uint32(bytes)?,
+                Ok(16) => msg.focused = r.read_bool(bytes)?,
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for BrowserFocusChanged {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.browser_id) as u64)
+        + 1 + sizeof_varint(*(&self.focused) as u64)
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_uint32(*&self.browser_id))?;
+        w.write_with_tag(16, |w| w.write_bool(*&self.focused))?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct BrowserVisibilityChanged {
+    pub browser_id: u32,
+    pub hidden: bool,
+}
+
+impl<'a> MessageRead<'a> for BrowserVisibilityChanged {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.browser_id = r.read_uint32(bytes)?,
+                Ok(16) => msg.hidden = r.read_bool(bytes)?,
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for BrowserVisibilityChanged {
+    fn get_size(&self) -> usize {
+        0
+        + 1 + sizeof_varint(*(&self.browser_id) as u64)
+        + 1 + sizeof_varint(*(&self.hidden) as u64)
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        w.write_with_tag(8, |w| w.write_uint32(*&self.browser_id))?;
+        w.write_with_tag(16, |w| w.write_bool(*&self.hidden))?;
+        Ok(())
+    }
+}
+
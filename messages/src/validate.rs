@@ -0,0 +1,656 @@
+use quick_protobuf::{Error, MessageRead, deserialize_from_slice};
+
+use std::fmt;
+
+use crate::packets::*;
+
+/// пределы для пакетов, пришедших с другой стороны соединения
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// размер всего закодированного пакета
+    pub max_packet_size: usize,
+    pub max_event_name: usize,
+    /// количество `EventValue` в `EmitEvent`
+    pub max_arguments: usize,
+    pub max_url: usize,
+    /// любая другая строка: аргументы событий, код JS, тексты ошибок и т.д.
+    pub max_string: usize,
+    /// на сколько уровней `Batch` может вкладываться друг в друга
+    pub max_depth: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Limits {
+        Limits {
+            max_packet_size: 10 * 1024 * 1024,
+            max_event_name: 256,
+            max_arguments: 128,
+            max_url: 8 * 1024,
+            max_string: 1024 * 1024,
+            max_depth: 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Packet,
+    EventName,
+    Arguments,
+    Url,
+    String,
+    Depth,
+}
+
+#[derive(Debug)]
+pub enum DecodeError {
+    Protobuf(quick_protobuf::Error),
+    LimitExceeded {
+        field: Field,
+        len: usize,
+        max: usize,
+    },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::Protobuf(err) => write!(f, "malformed packet: {}", err),
+            DecodeError::LimitExceeded { field, len, max } => {
+                write!(f, "{:?} limit exceeded: {} > {}", field, len, max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DecodeError::Protobuf(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<quick_protobuf::Error> for DecodeError {
+    fn from(err: quick_protobuf::Error) -> DecodeError {
+        DecodeError::Protobuf(err)
+    }
+}
+
+fn check(field: Field, len: usize, max: usize) -> Result<(), DecodeError> {
+    if len > max {
+        Err(DecodeError::LimitExceeded { field, len, max })
+    } else {
+        Ok(())
+    }
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Result<u64, DecodeError> {
+    let mut value = 0;
+
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*pos).ok_or(Error::UnexpectedEndOfBuffer)?;
+        *pos += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+
+        if byte < 0x80 {
+            return Ok(value);
+        }
+    }
+
+    Err(Error::Varint.into())
+}
+
+/// проходит по полям сообщения, не декодируя их
+///
+/// quick-protobuf верит длинам вложенных сообщений и паникует, если поле выходит за конец родителя,
+/// поэтому до декодирования все должно помещаться. `nested` - номера полей с сообщениями,
+/// они проверяются как плоские
+fn check_wire(bytes: &[u8], nested: &[u32]) -> Result<(), DecodeError> {
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        let tag = read_varint(bytes, &mut pos)?;
+
+        let len = match tag & 0x7 {
+            0 => {
+                read_varint(bytes, &mut pos)?;
+                continue;
+            }
+            1 => 8,
+            2 => read_varint(bytes, &mut pos)?,
+            5 => 4,
+            3 | 4 => return Err(Error::Deprecated("group").into()),
+            wire => return Err(Error::UnknownWireType(wire as u8).into()),
+        };
+
+        let field = bytes
+            .get(pos..)
+            .filter(|rest| len <= rest.len() as u64)
+            .map(|rest| &rest[..len as usize])
+            .ok_or(Error::UnexpectedEndOfBuffer)?;
+
+        if tag & 0x7 == 2 && nested.contains(&((tag >> 3) as u32)) {
+            check_wire(field, &[])?;
+        }
+
+        pos += field.len();
+    }
+
+    Ok(())
+}
+
+/// проверка декодированного сообщения по `Limits`
+pub trait Validate {
+    /// номера полей с вложенными сообщениями
+    const NESTED: &'static [u32] = &[];
+
+    fn validate(&self, limits: &Limits, depth: usize) -> Result<(), DecodeError>;
+}
+
+/// `deserialize_from_slice` с проверкой пределов, для всего, что пришло из сети
+pub fn decode<'a, T>(bytes: &'a [u8], limits: &Limits) -> Result<T, DecodeError>
+where
+    T: MessageRead<'a> + Validate,
+{
+    decode_nested(bytes, limits, 0)
+}
+
+/// `decode` для сообщения, вложенного в `depth` батчей
+pub fn decode_nested<'a, T>(
+    bytes: &'a [u8], limits: &Limits, depth: usize,
+) -> Result<T, DecodeError>
+where
+    T: MessageRead<'a> + Validate,
+{
+    check(Field::Packet, bytes.len(), limits.max_packet_size)?;
+
+    // сообщение с префиксом длины, как пишет `serialize_into_vec`
+    let mut pos = 0;
+    let len = read_varint(bytes, &mut pos)?;
+    let message = bytes
+        .get(pos..)
+        .filter(|rest| len <= rest.len() as u64)
+        .map(|rest| &rest[..len as usize])
+        .ok_or(Error::UnexpectedEndOfBuffer)?;
+
+    check_wire(message, T::NESTED)?;
+
+    let message: T = deserialize_from_slice(bytes)?;
+    message.validate(limits, depth)?;
+
+    Ok(message)
+}
+
+macro_rules! impl_validate {
+    ($($type:ty),* $(,)?) => {
+        $(
+            impl Validate for $type {
+                fn validate(&self, _: &Limits, _: usize) -> Result<(), DecodeError> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_validate!(
+    RequestJoin,
+    JoinResponse,
+    DestroyBrowser,
+    AlwaysListenKeys,
+    HideBrowser,
+    FocusBrowser,
+    BrowserCreated,
+    Got,
+    OpenConnection,
+    AppendToObject,
+    RemoveFromObject,
+    ToggleDevTools,
+    SetAudioSettings,
+    BrowserDestroyed,
+    RendererCrashed,
+    NavigateBrowser,
+    LoadingStateChanged,
+    SetBrowserRect,
+    SetBrowserLayer,
+    SetZoom,
+    SetBrowserAudio,
+    SetBrowserMaxFps,
+    SetLowPowerMode,
+    TakeScreenshot,
+    // размер куска ограничен размером пакета
+    ScreenshotChunk<'_>,
+    AssetsReady,
+    BrowserFocusChanged,
+    BrowserVisibilityChanged,
+);
+
+/// содержимое проверяет обработчик при декодировании, вложенный `Batch` - через `decode_nested`
+impl Validate for Packet<'_> {
+    fn validate(&self, limits: &Limits, _: usize) -> Result<(), DecodeError> {
+        check(Field::Packet, self.bytes.len(), limits.max_packet_size)
+    }
+}
+
+impl Validate for Batch<'_> {
+    const NESTED: &'static [u32] = &[1];
+
+    fn validate(&self, limits: &Limits, depth: usize) -> Result<(), DecodeError> {
+        check(Field::Depth, depth + 1, limits.max_depth)?;
+
+        self.packets
+            .iter()
+            .try_for_each(|packet| packet.validate(limits, depth))
+    }
+}
+
+impl Validate for EventValue<'_> {
+    fn validate(&self, limits: &Limits, _: usize) -> Result<(), DecodeError> {
+        let len = self.string_value.as_ref().map(|s| s.len()).unwrap_or(0);
+        check(Field::String, len, limits.max_string)
+    }
+}
+
+impl Validate for EmitEvent<'_> {
+    const NESTED: &'static [u32] = &[3];
+
+    fn validate(&self, limits: &Limits, depth: usize) -> Result<(), DecodeError> {
+        check(
+            Field::EventName,
+            self.event_name.len(),
+            limits.max_event_name,
+        )?;
+        check(Field::Arguments, self.arguments.len(), limits.max_arguments)?;
+
+        let len = self.args.as_ref().map(|s| s.len()).unwrap_or(0);
+        check(Field::String, len, limits.max_string)?;
+
+        self.arguments
+            .iter()
+            .try_for_each(|value| value.validate(limits, depth))
+    }
+}
+
+impl Validate for CreateBrowser<'_> {
+    fn validate(&self, limits: &Limits, _: usize) -> Result<(), DecodeError> {
+        check(Field::Url, self.url.len(), limits.max_url)
+    }
+}
+
+impl Validate for CreateExternalBrowser<'_> {
+    fn validate(&self, limits: &Limits, _: usize) -> Result<(), DecodeError> {
+        check(Field::Url, self.url.len(), limits.max_url)?;
+        check(Field::String, self.texture.len(), limits.max_string)
+    }
+}
+
+impl Validate for LoadUrl<'_> {
+    fn validate(&self, limits: &Limits, _: usize) -> Result<(), DecodeError> {
+        check(Field::Url, self.url.len(), limits.max_url)
+    }
+}
+
+impl Validate for ExecuteJavaScript<'_> {
+    fn validate(&self, limits: &Limits, _: usize) -> Result<(), DecodeError> {
+        check(Field::String, self.code.len(), limits.max_string)
+    }
+}
+
+impl Validate for JavaScriptResult<'_> {
+    fn validate(&self, limits: &Limits, _: usize) -> Result<(), DecodeError> {
+        check(Field::String, self.result.len(), limits.max_string)
+    }
+}
+
+impl Validate for BrowserLoaded<'_> {
+    fn validate(&self, limits: &Limits, _: usize) -> Result<(), DecodeError> {
+        check(Field::Url, self.url.len(), limits.max_url)
+    }
+}
+
+impl Validate for BrowserLoadError<'_> {
+    fn validate(&self, limits: &Limits, _: usize) -> Result<(), DecodeError> {
+        check(Field::Url, self.url.len(), limits.max_url)?;
+        check(Field::String, self.error_text.len(), limits.max_string)
+    }
+}
+
+impl Validate for ClientInfo<'_> {
+    fn validate(&self, limits: &Limits, _: usize) -> Result<(), DecodeError> {
+        check(Field::Arguments, self.plugins.len(), limits.max_arguments)?;
+
+        [&self.cef_version, &self.chromium_version, &self.locale]
+            .into_iter()
+            .chain(self.plugins.iter())
+            .try_for_each(|s| check(Field::String, s.len(), limits.max_string))
+    }
+}
+
+impl Validate for AssetManifest<'_> {
+    const NESTED: &'static [u32] = &[1];
+
+    fn validate(&self, limits: &Limits, _: usize) -> Result<(), DecodeError> {
+        self.files.iter().try_for_each(|file| {
+            check(Field::Url, file.path.len(), limits.max_url)?;
+            check(Field::String, file.hash.len(), limits.max_string)
+        })
+    }
+}
+
+impl Validate for RequestAssets<'_> {
+    fn validate(&self, limits: &Limits, _: usize) -> Result<(), DecodeError> {
+        self.paths
+            .iter()
+            .try_for_each(|path| check(Field::Url, path.len(), limits.max_url))
+    }
+}
+
+impl Validate for AssetChunk<'_> {
+    fn validate(&self, limits: &Limits, _: usize) -> Result<(), DecodeError> {
+        check(Field::Url, self.path.len(), limits.max_url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::try_into_packet;
+
+    use proptest::prelude::*;
+    use std::borrow::Cow;
+
+    /// последний `PacketId`, см. `packets.proto`
+    const MAX_PACKET_ID: i32 = PacketId::BROWSER_VISIBILITY_CHANGED as i32;
+
+    fn packet(packet_id: PacketId, bytes: Vec<u8>) -> Packet<'static> {
+        Packet {
+            packet_id,
+            bytes: Cow::Owned(bytes),
+            seq: None,
+        }
+    }
+
+    fn nested_batch(depth: usize) -> Vec<u8> {
+        let mut bytes = quick_protobuf::serialize_into_vec(&Batch::default()).unwrap();
+
+        for _ in 0..depth {
+            let batch = Batch {
+                packets: vec![packet(PacketId::BATCH, bytes)],
+            };
+
+            bytes = quick_protobuf::serialize_into_vec(&batch).unwrap();
+        }
+
+        quick_protobuf::serialize_into_vec(&packet(PacketId::BATCH, bytes)).unwrap()
+    }
+
+    fn decode_as<'a, T>(bytes: &'a [u8], limits: &Limits) -> Result<(), DecodeError>
+    where
+        T: MessageRead<'a> + Validate,
+    {
+        decode::<T>(bytes, limits).map(|_| ())
+    }
+
+    /// разбирает содержимое пакета по `packet_id`, как обработчики клиента и сервера
+    fn decode_payload(packet: &Packet, limits: &Limits, depth: usize) -> Result<(), DecodeError> {
+        let bytes = &packet.bytes;
+
+        match packet.packet_id {
+            PacketId::OPEN_CONNECTION => decode_as::<OpenConnection>(bytes, limits),
+            PacketId::REQUEST_JOIN => decode_as::<RequestJoin>(bytes, limits),
+            PacketId::JOIN_RESPONSE => decode_as::<JoinResponse>(bytes, limits),
+            PacketId::CREATE_BROWSER => decode_as::<CreateBrowser>(bytes, limits),
+            PacketId::DESTROY_BROWSER => decode_as::<DestroyBrowser>(bytes, limits),
+            PacketId::ALWAYS_LISTEN_KEYS => decode_as::<AlwaysListenKeys>(bytes, limits),
+            PacketId::HIDE_BROWSER => decode_as::<HideBrowser>(bytes, limits),
+            PacketId::FOCUS_BROWSER => decode_as::<FocusBrowser>(bytes, limits),
+            PacketId::EMIT_EVENT => decode_as::<EmitEvent>(bytes, limits),
+            PacketId::BROWSER_CREATED => decode_as::<BrowserCreated>(bytes, limits),
+            PacketId::GOT => decode_as::<Got>(bytes, limits),
+            PacketId::CREATE_EXTERNAL_BROWSER => decode_as::<CreateExternalBrowser>(bytes, limits),
+            PacketId::APPEND_TO_OBJECT => decode_as::<AppendToObject>(bytes, limits),
+            PacketId::REMOVE_FROM_OBJECT => decode_as::<RemoveFromObject>(bytes, limits),
+            PacketId::TOGGLE_DEV_TOOLS => decode_as::<ToggleDevTools>(bytes, limits),
+            PacketId::SET_AUDIO_SETTINGS => decode_as::<SetAudioSettings>(bytes, limits),
+            PacketId::LOAD_URL => decode_as::<LoadUrl>(bytes, limits),
+            PacketId::EXECUTE_JAVASCRIPT => decode_as::<ExecuteJavaScript>(bytes, limits),
+            PacketId::JAVASCRIPT_RESULT => decode_as::<JavaScriptResult>(bytes, limits),
+            PacketId::BROWSER_LOADED => decode_as::<BrowserLoaded>(bytes, limits),
+            PacketId::BROWSER_LOAD_ERROR => decode_as::<BrowserLoadError>(bytes, limits),
+            PacketId::BROWSER_DESTROYED => decode_as::<BrowserDestroyed>(bytes, limits),
+            PacketId::RENDERER_CRASHED => decode_as::<RendererCrashed>(bytes, limits),
+            PacketId::CLIENT_INFO => decode_as::<ClientInfo>(bytes, limits),
+            PacketId::BATCH => {
+                let batch: Batch = decode_nested(bytes, limits, depth)?;
+
+                batch
+                    .packets
+                    .iter()
+                    .try_for_each(|packet| decode_payload(packet, limits, depth + 1))
+            }
+            PacketId::NAVIGATE_BROWSER => decode_as::<NavigateBrowser>(bytes, limits),
+            PacketId::LOADING_STATE_CHANGED => decode_as::<LoadingStateChanged>(bytes, limits),
+            PacketId::SET_BROWSER_RECT => decode_as::<SetBrowserRect>(bytes, limits),
+            PacketId::SET_BROWSER_LAYER => decode_as::<SetBrowserLayer>(bytes, limits),
+            PacketId::SET_ZOOM => decode_as::<SetZoom>(bytes, limits),
+            PacketId::SET_BROWSER_AUDIO => decode_as::<SetBrowserAudio>(bytes, limits),
+            PacketId::SET_BROWSER_MAX_FPS => decode_as::<SetBrowserMaxFps>(bytes, limits),
+            PacketId::SET_LOW_POWER_MODE => decode_as::<SetLowPowerMode>(bytes, limits),
+            PacketId::TAKE_SCREENSHOT => decode_as::<TakeScreenshot>(bytes, limits),
+            PacketId::SCREENSHOT_CHUNK => decode_as::<ScreenshotChunk>(bytes, limits),
+            PacketId::ASSET_MANIFEST => decode_as::<AssetManifest>(bytes, limits),
+            PacketId::ASSET_CHUNK => decode_as::<AssetChunk>(bytes, limits),
+            PacketId::REQUEST_ASSETS => decode_as::<RequestAssets>(bytes, limits),
+            PacketId::ASSETS_READY => decode_as::<AssetsReady>(bytes, limits),
+            PacketId::BROWSER_FOCUS_CHANGED => decode_as::<BrowserFocusChanged>(bytes, limits),
+            PacketId::BROWSER_VISIBILITY_CHANGED => {
+                decode_as::<BrowserVisibilityChanged>(bytes, limits)
+            }
+        }
+    }
+
+    fn emit_event(name: &str, count: usize) -> Vec<u8> {
+        let event = EmitEvent {
+            event_name: name.into(),
+            args: None,
+            arguments: vec![
+                EventValue {
+                    integer_value: Some(1),
+                    ..Default::default()
+                };
+                count
+            ],
+            browser_id: None,
+        };
+
+        quick_protobuf::serialize_into_vec(&event).unwrap()
+    }
+
+    #[test]
+    fn decode_valid_packet() {
+        let bytes = try_into_packet(LoadUrl {
+            browser_id: 1,
+            url: "https://example.com".into(),
+        })
+        .unwrap();
+
+        let packet: Packet = decode(&bytes, &Limits::default()).unwrap();
+        let load: LoadUrl = decode(&packet.bytes, &Limits::default()).unwrap();
+
+        assert_eq!(load.url, "https://example.com");
+    }
+
+    #[test]
+    fn decode_rejects_long_url() {
+        let limits = Limits {
+            max_url: 8,
+            ..Default::default()
+        };
+
+        let bytes = quick_protobuf::serialize_into_vec(&LoadUrl {
+            browser_id: 1,
+            url: "https://example.com".into(),
+        })
+        .unwrap();
+
+        assert!(matches!(
+            decode::<LoadUrl>(&bytes, &limits),
+            Err(DecodeError::LimitExceeded {
+                field: Field::Url,
+                len: 19,
+                max: 8,
+            })
+        ));
+    }
+
+    #[test]
+    fn decode_rejects_long_event_name() {
+        let limits = Limits {
+            max_event_name: 4,
+            ..Default::default()
+        };
+
+        let bytes = emit_event("too_long", 0);
+
+        assert!(matches!(
+            decode::<EmitEvent>(&bytes, &limits),
+            Err(DecodeError::LimitExceeded {
+                field: Field::EventName,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn decode_rejects_too_many_arguments() {
+        let limits = Limits {
+            max_arguments: 2,
+            ..Default::default()
+        };
+
+        assert!(decode::<EmitEvent>(&emit_event("event", 2), &limits).is_ok());
+        assert!(matches!(
+            decode::<EmitEvent>(&emit_event("event", 3), &limits),
+            Err(DecodeError::LimitExceeded {
+                field: Field::Arguments,
+                len: 3,
+                max: 2,
+            })
+        ));
+    }
+
+    #[test]
+    fn decode_rejects_deep_batches() {
+        let limits = Limits {
+            max_depth: 3,
+            ..Default::default()
+        };
+
+        let shallow = nested_batch(2);
+        let deep = nested_batch(3);
+
+        let packet: Packet = decode(&shallow, &limits).unwrap();
+        assert!(decode_payload(&packet, &limits, 0).is_ok());
+
+        let packet: Packet = decode(&deep, &limits).unwrap();
+        assert!(matches!(
+            decode_payload(&packet, &limits, 0),
+            Err(DecodeError::LimitExceeded {
+                field: Field::Depth,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn decode_rejects_large_packet() {
+        let limits = Limits {
+            max_packet_size: 16,
+            ..Default::default()
+        };
+
+        let bytes = try_into_packet(ExecuteJavaScript {
+            browser_id: 1,
+            code: "console.log('hello world')".into(),
+            request_id: None,
+        })
+        .unwrap();
+
+        assert!(matches!(
+            decode::<Packet>(&bytes, &limits),
+            Err(DecodeError::LimitExceeded {
+                field: Field::Packet,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn max_packet_id_is_last() {
+        assert_eq!(PacketId::from(MAX_PACKET_ID + 1), PacketId::default());
+    }
+
+    proptest! {
+        #[test]
+        fn random_bytes_never_panic(bytes in proptest::collection::vec(any::<u8>(), 0..512)) {
+            let limits = Limits::default();
+
+            let _ = decode::<Packet>(&bytes, &limits);
+
+            for id in 0..=MAX_PACKET_ID {
+                let _ = decode_payload(&packet(PacketId::from(id), bytes.clone()), &limits, 0);
+            }
+        }
+
+        #[test]
+        fn random_packet_payloads_never_panic(
+            id in 0..=MAX_PACKET_ID,
+            payload in proptest::collection::vec(any::<u8>(), 0..512),
+            batched in any::<bool>(),
+        ) {
+            let limits = Limits::default();
+            let packet = packet(PacketId::from(id), payload);
+            let bytes = if batched {
+                try_into_packet(Batch {
+                    packets: vec![packet],
+                })
+                .unwrap()
+            } else {
+                quick_protobuf::serialize_into_vec(&packet).unwrap()
+            };
+
+            if let Ok(packet) = decode::<Packet>(&bytes, &limits) {
+                let _ = decode_payload(&packet, &limits, 0);
+            }
+        }
+
+        #[test]
+        fn decoded_packets_respect_limits(
+            name in "[a-z_]{0,32}",
+            count in 0usize..16,
+            max_event_name in 0usize..32,
+            max_arguments in 0usize..16,
+        ) {
+            let limits = Limits {
+                max_event_name,
+                max_arguments,
+                ..Default::default()
+            };
+
+            let bytes = emit_event(&name, count);
+
+            match decode::<EmitEvent>(&bytes, &limits) {
+                Ok(event) => {
+                    prop_assert!(event.event_name.len() <= max_event_name);
+                    prop_assert!(event.arguments.len() <= max_arguments);
+                }
+                Err(DecodeError::LimitExceeded { .. }) => {
+                    prop_assert!(name.len() > max_event_name || count > max_arguments);
+                }
+                Err(err) => prop_assert!(false, "unexpected error: {}", err),
+            }
+        }
+    }
+}
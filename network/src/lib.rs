@@ -1,7 +1,8 @@
-use quinn::{Connecting, Connection, Endpoint};
+use quinn::{Connecting, Connection, ConnectionError, Endpoint};
 
 use slotmap::{SecondaryMap, SlotMap, new_key_type};
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use tokio::{
     runtime::Runtime,
     sync::{
@@ -22,15 +23,41 @@ const INCOMING_PACKET_SIZE: usize = 10 * 1024 * 1024; // 10Mb
 pub enum CertStrategy {
     // LetsEncrypt(String),
     SelfSigned,
+    /// сертификат (цепочка) и приватный ключ в PEM
+    Files { certificate: PathBuf, key: PathBuf },
 }
 
 pub enum Event {
     Connected(PeerId, SocketAddr),
     Message(PeerId, Vec<u8>),
-    Disconnect(PeerId, SocketAddr),
+    Disconnect(PeerId, SocketAddr, DisconnectReason),
     ConnectionError(PeerId),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DisconnectReason {
+    /// пир не отвечал
+    TimedOut,
+    /// пир закрыл соединение сам
+    Closed,
+    /// соединение закрыла эта сторона
+    Local,
+    Error,
+}
+
+impl From<&ConnectionError> for DisconnectReason {
+    fn from(err: &ConnectionError) -> Self {
+        match err {
+            ConnectionError::TimedOut => DisconnectReason::TimedOut,
+            ConnectionError::ApplicationClosed(_) | ConnectionError::ConnectionClosed(_) => {
+                DisconnectReason::Closed
+            }
+            ConnectionError::LocallyClosed => DisconnectReason::Local,
+            _ => DisconnectReason::Error,
+        }
+    }
+}
+
 #[derive(Debug)]
 enum WorkerEvent {
     Connected(
@@ -40,7 +67,7 @@ enum WorkerEvent {
         Option<PeerId>,
     ),
     Message(PeerId, Vec<u8>),
-    Disconnect(PeerId),
+    Disconnect(PeerId, DisconnectReason),
     ConnectionError(PeerId),
 }
 
@@ -73,11 +100,16 @@ impl Socket {
         Ok(Self::setup(runtime, endpoint, false))
     }
 
-    pub fn new_server(addr: SocketAddr, _cert: CertStrategy) -> anyhow::Result<Self> {
+    pub fn new_server(addr: SocketAddr, cert: CertStrategy) -> anyhow::Result<Self> {
         let runtime = Runtime::new()?;
         let _guard = runtime.enter();
 
-        let endpoint = server::make_self_signed(addr)?;
+        let endpoint = match cert {
+            CertStrategy::SelfSigned => server::make_self_signed(addr)?,
+            CertStrategy::Files { certificate, key } => {
+                server::make_from_files(addr, &certificate, &key)?
+            }
+        };
 
         Ok(Self::setup(runtime, endpoint, true))
     }
@@ -139,11 +171,12 @@ impl Socket {
                 return Some(Event::Message(peer_id, bytes));
             }
 
-            WorkerEvent::Disconnect(peer_id) => {
+            WorkerEvent::Disconnect(peer_id, reason) => {
                 self.peers_id.remove(peer_id);
 
                 if let Some(peer) = self.peers.remove(peer_id) {
-                    return Some(Event::Disconnect(peer_id, peer.connection.remote_address()));
+                    let addr = peer.connection.remote_address();
+                    return Some(Event::Disconnect(peer_id, addr, reason));
                 }
             }
 
@@ -183,7 +216,8 @@ async fn worker_task(
                             Some(peer_id),
                         ));
                     }
-                    Err(_) => {
+                    Err(err) => {
+                        log::warn!("couldn't connect to {}: {}", addr, err);
                         let _ = event_tx.send(WorkerEvent::ConnectionError(peer_id));
                     }
                 }
@@ -201,8 +235,14 @@ async fn accept_connections(endpoint: Endpoint, event_tx: crossbeam_channel::Sen
     while let Some(incoming) = endpoint.accept().await {
         let event_tx = event_tx.clone();
         tokio::spawn(async move {
-            if let Ok(connecting) = incoming.accept() {
-                let _ = process_connection(connecting, event_tx, None).await;
+            let addr = incoming.remote_address();
+
+            match incoming.accept() {
+                Ok(connecting) => {
+                    let _ = process_connection(connecting, event_tx, None).await;
+                }
+
+                Err(err) => log::warn!("couldn't accept connection from {}: {}", addr, err),
             }
         });
     }
@@ -212,9 +252,13 @@ async fn process_connection(
     connecting: Connecting, event_tx: crossbeam_channel::Sender<WorkerEvent>,
     peer_id: Option<PeerId>,
 ) -> anyhow::Result<()> {
+    let addr = connecting.remote_address();
+
     let connection = match connecting.await {
         Ok(conn) => conn,
-        Err(_) => {
+        Err(err) => {
+            log::warn!("handshake with {} failed: {}", addr, err);
+
             if let Some(peer_id) = peer_id {
                 let _ = event_tx.send(WorkerEvent::ConnectionError(peer_id));
             }
@@ -253,13 +297,19 @@ async fn notify_about_incoming(
 async fn listen_to_streams(
     connection: Connection, peer_id: PeerId, event_tx: crossbeam_channel::Sender<WorkerEvent>,
 ) {
-    while let Ok(mut stream) = connection.accept_uni().await {
-        if let Ok(bytes) = stream.read_to_end(INCOMING_PACKET_SIZE).await
-            && event_tx.send(WorkerEvent::Message(peer_id, bytes)).is_err()
-        {
-            break;
+    let reason = loop {
+        match connection.accept_uni().await {
+            Ok(mut stream) => {
+                if let Ok(bytes) = stream.read_to_end(INCOMING_PACKET_SIZE).await
+                    && event_tx.send(WorkerEvent::Message(peer_id, bytes)).is_err()
+                {
+                    break DisconnectReason::Local;
+                }
+            }
+
+            Err(err) => break DisconnectReason::from(&err),
         }
-    }
+    };
 
-    let _ = event_tx.send(WorkerEvent::Disconnect(peer_id));
+    let _ = event_tx.send(WorkerEvent::Disconnect(peer_id, reason));
 }
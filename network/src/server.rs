@@ -1,5 +1,8 @@
+use anyhow::Context;
 use quinn::{Endpoint, ServerConfig, TransportConfig};
-use rustls::pki_types::{CertificateDer, PrivatePkcs8KeyDer};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -8,17 +11,31 @@ pub fn make_self_signed(bind_addr: std::net::SocketAddr) -> anyhow::Result<Endpo
     let cert_der = CertificateDer::from(cert.cert);
     let priv_key = PrivatePkcs8KeyDer::from(cert.signing_key.serialize_der());
 
-    let server_config = configure_server(cert_der, priv_key)?;
+    let server_config = configure_server(vec![cert_der], priv_key.into())?;
+    Ok(Endpoint::server(server_config, bind_addr)?)
+}
+
+pub fn make_from_files(
+    bind_addr: std::net::SocketAddr, certificate: &Path, key: &Path,
+) -> anyhow::Result<Endpoint> {
+    let certs = CertificateDer::pem_file_iter(certificate)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .with_context(|| format!("couldn't read certificate {}", certificate.display()))?;
+
+    let priv_key = PrivateKeyDer::from_pem_file(key)
+        .with_context(|| format!("couldn't read private key {}", key.display()))?;
+
+    let server_config = configure_server(certs, priv_key)?;
     Ok(Endpoint::server(server_config, bind_addr)?)
 }
 
 fn configure_server(
-    cert: CertificateDer<'static>, priv_key: PrivatePkcs8KeyDer<'static>,
+    certs: Vec<CertificateDer<'static>>, priv_key: PrivateKeyDer<'static>,
 ) -> anyhow::Result<ServerConfig> {
     let mut transport_config = TransportConfig::default();
     transport_config.keep_alive_interval(Some(Duration::from_secs(1)));
 
-    let mut server_config = ServerConfig::with_single_cert(vec![cert], priv_key.into())?;
+    let mut server_config = ServerConfig::with_single_cert(certs, priv_key)?;
     server_config.transport = Arc::new(transport_config);
 
     Ok(server_config)
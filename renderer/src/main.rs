@@ -73,6 +73,71 @@ impl V8Handler for Handler {
                 return true;
             }
 
+            "set_layer" => {
+                let msg = ProcessMessage::create("set_layer");
+                let list = msg.argument_list();
+
+                if args.len() != 1 {
+                    return true;
+                }
+
+                convert_to_list(&args, &list);
+
+                self.frame
+                    .browser()
+                    .main_frame()
+                    .send_process_message(ProcessId::Browser, msg);
+
+                return true;
+            }
+
+            "set_zoom" => {
+                let msg = ProcessMessage::create("set_zoom");
+                let list = msg.argument_list();
+
+                if args.len() != 1 {
+                    return true;
+                }
+
+                convert_to_list(&args, &list);
+
+                self.frame
+                    .browser()
+                    .main_frame()
+                    .send_process_message(ProcessId::Browser, msg);
+
+                return true;
+            }
+
+            "set_volume" | "set_muted" => {
+                let msg = ProcessMessage::create(&name);
+                let list = msg.argument_list();
+
+                if args.len() != 1 {
+                    return true;
+                }
+
+                convert_to_list(&args, &list);
+
+                self.frame
+                    .browser()
+                    .main_frame()
+                    .send_process_message(ProcessId::Browser, msg);
+
+                return true;
+            }
+
+            "take_screenshot" => {
+                let msg = ProcessMessage::create("take_screenshot");
+
+                self.frame
+                    .browser()
+                    .main_frame()
+                    .send_process_message(ProcessId::Browser, msg);
+
+                return true;
+            }
+
             "on" => {
                 if args.len() != 2 {
                     return true;
@@ -183,6 +248,11 @@ impl RenderProcessHandler for Application {
         let func_on = V8Value::new_function("on", Some(handler.clone()));
         let func_off = V8Value::new_function("off", Some(handler.clone()));
         let func_hide = V8Value::new_function("hide", Some(handler.clone()));
+        let func_layer = V8Value::new_function("set_layer", Some(handler.clone()));
+        let func_zoom = V8Value::new_function("set_zoom", Some(handler.clone()));
+        let func_volume = V8Value::new_function("set_volume", Some(handler.clone()));
+        let func_muted = V8Value::new_function("set_muted", Some(handler.clone()));
+        let func_screenshot = V8Value::new_function("take_screenshot", Some(handler.clone()));
         let func_emit = V8Value::new_function("emit", Some(handler));
 
         let key_str = CefString::new("version");
@@ -191,10 +261,20 @@ impl RenderProcessHandler for Application {
         let key_off = CefString::new("off");
         let key_emit = CefString::new("emit");
         let key_hide = CefString::new("hide");
+        let key_layer = CefString::new("set_layer");
+        let key_zoom = CefString::new("set_zoom");
+        let key_volume = CefString::new("set_volume");
+        let key_muted = CefString::new("set_muted");
+        let key_screenshot = CefString::new("take_screenshot");
 
         cef_obj.set_value_by_key(&key_str, &version);
         cef_obj.set_value_by_key(&key_focus, &func_focus);
         cef_obj.set_value_by_key(&key_hide, &func_hide);
+        cef_obj.set_value_by_key(&key_layer, &func_layer);
+        cef_obj.set_value_by_key(&key_zoom, &func_zoom);
+        cef_obj.set_value_by_key(&key_volume, &func_volume);
+        cef_obj.set_value_by_key(&key_muted, &func_muted);
+        cef_obj.set_value_by_key(&key_screenshot, &func_screenshot);
         cef_obj.set_value_by_key(&key_on, &func_on);
         cef_obj.set_value_by_key(&key_off, &func_off);
         cef_obj.set_value_by_key(&key_emit, &func_emit);
@@ -217,10 +297,31 @@ impl RenderProcessHandler for Application {
     fn on_webkit_initialized(&self) {}
 
     fn on_process_message(
-        &self, _browser: Browser, _frame: Frame, _source: ProcessId, msg: ProcessMessage,
+        &self, _browser: Browser, frame: Frame, _source: ProcessId, msg: ProcessMessage,
     ) -> bool {
         let name = msg.name().to_string();
 
+        if name == "execute_js" {
+            let args = msg.argument_list();
+            let code = args.string(0).to_string();
+            let request_id = args.integer(1);
+
+            let (success, result) = match frame.context().eval(&code) {
+                Ok(value) => (true, v8_to_string(&value)),
+                Err(exception) => (false, exception.to_string()),
+            };
+
+            let reply = ProcessMessage::create("js_result");
+            let list = reply.argument_list();
+            list.set_integer(0, request_id);
+            list.set_bool(1, success);
+            list.set_string(2, &CefString::new(&result));
+
+            frame.send_process_message(ProcessId::Browser, reply);
+
+            return true;
+        }
+
         if name == "trigger_event" {
             let args = msg.argument_list();
             let event = args.string(0).to_string();
@@ -329,6 +430,22 @@ fn convert_to_list(v8: &[V8Value], pm: &List) {
     }
 }
 
+fn v8_to_string(value: &V8Value) -> String {
+    if value.is_string() {
+        value.string().to_string()
+    } else if value.is_bool() {
+        value.bool().to_string()
+    } else if value.is_integer() {
+        value.integer().to_string()
+    } else if value.is_double() {
+        value.double().to_string()
+    } else if value.is_undefined() {
+        "undefined".to_string()
+    } else {
+        String::new()
+    }
+}
+
 fn convert_to_v8(pm: &List, offset: usize, v8: &mut Vec<V8Value>) {
     for idx in offset..pm.len() {
         match pm.get_type(idx) {
@@ -0,0 +1,148 @@
+use log::{info, warn};
+use messages::packets::{AssetChunk, AssetFile, AssetManifest};
+use sha2::{Digest, Sha256};
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// такие же пределы у клиента, больше он все равно не скачает
+const MAX_ASSET_FILE_SIZE: usize = 32 * 1024 * 1024;
+const MAX_ASSETS_SIZE: usize = 256 * 1024 * 1024;
+const MAX_ASSET_FILES: usize = 4096;
+
+/// файлы уходят кусками, чтобы не упираться в лимит размера пакета
+const ASSET_CHUNK_SIZE: usize = 256 * 1024;
+
+struct Asset {
+    hash: Vec<u8>,
+    bytes: Vec<u8>,
+}
+
+/// содержимое `assets_dir` из `cef.toml`, которое раздается игрокам при входе
+#[derive(Default)]
+pub struct Assets {
+    files: BTreeMap<String, Asset>,
+    size: usize,
+}
+
+impl Assets {
+    pub fn load(root: &Path) -> Assets {
+        let mut assets = Assets::default();
+
+        if root.is_dir() {
+            assets.load_dir(root, root);
+
+            info!(
+                "Loaded {} CEF assets ({} bytes) from {}",
+                assets.files.len(),
+                assets.size,
+                root.display()
+            );
+        }
+
+        assets
+    }
+
+    fn load_dir(&mut self, root: &Path, dir: &Path) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            warn!("couldn't read assets dir {}", dir.display());
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+
+            // ссылки не трогаем, чтобы не выйти за пределы папки и не зациклиться
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+
+            if file_type.is_dir() {
+                self.load_dir(root, &path);
+                continue;
+            }
+
+            if !file_type.is_file() {
+                continue;
+            }
+
+            // путь для клиента всегда через `/`
+            let Some(name) = path.strip_prefix(root).ok().and_then(|relative| {
+                relative
+                    .components()
+                    .map(|component| component.as_os_str().to_str())
+                    .collect::<Option<Vec<_>>>()
+                    .map(|segments| segments.join("/"))
+            }) else {
+                warn!("skip asset with a non UTF-8 name {}", path.display());
+                continue;
+            };
+
+            let Ok(bytes) = std::fs::read(&path) else {
+                warn!("couldn't read asset {}", name);
+                continue;
+            };
+
+            if bytes.len() > MAX_ASSET_FILE_SIZE
+                || self.size + bytes.len() > MAX_ASSETS_SIZE
+                || self.files.len() >= MAX_ASSET_FILES
+            {
+                warn!(
+                    "skip asset {} ({} bytes), limits exceeded",
+                    name,
+                    bytes.len()
+                );
+                continue;
+            }
+
+            let hash = Sha256::digest(&bytes).to_vec();
+
+            self.size += bytes.len();
+            self.files.insert(name, Asset { hash, bytes });
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn manifest(&self) -> AssetManifest<'_> {
+        let files = self
+            .files
+            .iter()
+            .map(|(path, asset)| AssetFile {
+                path: Cow::Borrowed(path),
+                size: asset.bytes.len() as u64,
+                hash: Cow::Borrowed(&asset.hash),
+            })
+            .collect();
+
+        AssetManifest { files }
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.files.contains_key(path)
+    }
+
+    /// `None` для файлов не из манифеста и после последнего куска
+    pub fn chunk<'a>(&'a self, path: &'a str, index: u32) -> Option<AssetChunk<'a>> {
+        let asset = self.files.get(path)?;
+        // пустой файл - один пустой кусок
+        let total = asset.bytes.len().div_ceil(ASSET_CHUNK_SIZE).max(1) as u32;
+
+        if index >= total {
+            return None;
+        }
+
+        let start = index as usize * ASSET_CHUNK_SIZE;
+        let end = asset.bytes.len().min(start + ASSET_CHUNK_SIZE);
+
+        Some(AssetChunk {
+            path: Cow::Borrowed(path),
+            index,
+            total,
+            data: Cow::Borrowed(&asset.bytes[start..end]),
+        })
+    }
+}
@@ -0,0 +1,40 @@
+use std::collections::BTreeSet;
+
+/// то, что сервер знает о браузере игрока по отправленным пакетам и ответам клиента
+#[derive(Debug, Clone, Default)]
+pub struct Browser {
+    /// последний запрошенный или загруженный клиентом адрес
+    pub url: String,
+    pub hidden: bool,
+    pub focused: bool,
+    /// клиент сообщил, что браузер создан
+    pub created: bool,
+    /// текстура внешнего браузера, `None` для обычного
+    pub texture: Option<String>,
+    pub scale: i32,
+    pub objects: BTreeSet<i32>,
+    /// `max_distance` и `reference_distance` из `cef_set_audio_settings`
+    pub audio_settings: Option<(f32, f32)>,
+    pub volume: Option<f32>,
+    pub muted: Option<bool>,
+}
+
+impl Browser {
+    pub fn new(url: String, hidden: bool, focused: bool) -> Browser {
+        Browser {
+            url,
+            hidden,
+            focused,
+            ..Default::default()
+        }
+    }
+
+    pub fn external(url: String, texture: String, scale: i32) -> Browser {
+        Browser {
+            url,
+            texture: Some(texture),
+            scale,
+            ..Default::default()
+        }
+    }
+}
@@ -1,6 +1,28 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::net::SocketAddr;
+use std::time::{Duration, Instant};
 
+use messages::packets::Packet;
 use network::PeerId;
+use quick_protobuf::MessageWrite;
+
+use crate::assets::Assets;
+
+/// первая версия клиента, которая отвечает `Got` на пакеты с `seq`
+const ACK_MIN_VERSION: i32 = 0x00_01_01;
+
+/// первая версия клиента, которая понимает `Batch`
+const BATCH_MIN_VERSION: i32 = 0x00_01_02;
+
+/// первая версия клиента, которая присылает аргументы событий с типами
+const TYPED_EVENTS_MIN_VERSION: i32 = 0x00_01_03;
+
+/// сколько последних доставленных пакетов помним
+const DELIVERED_HISTORY: usize = 1024;
+
+/// предел размера скриншота, собираемого из кусков
+const MAX_SCREENSHOT_SIZE: usize = 16 * 1024 * 1024;
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub enum State {
@@ -8,12 +30,77 @@ pub enum State {
     Connected,
 }
 
+/// то, что клиент сообщил о себе пакетом `ClientInfo`
+#[derive(Debug, Clone)]
+pub struct ClientInfo {
+    pub width: u32,
+    pub height: u32,
+    pub cef_version: String,
+    pub chromium_version: String,
+    pub locale: String,
+    pub render_mode: i32,
+    pub plugins: Vec<String>,
+}
+
 #[derive(Debug)]
 pub struct Client {
     id: i32, // SA:MP player id
     state: State,
     addr: SocketAddr,
     peer: PeerId,
+    version: i32,
+    seq: u32,
+    delivered: BTreeSet<u32>,
+    info: Option<ClientInfo>,
+    outgoing: Vec<Packet<'static>>,
+    screenshots: HashMap<u32, Screenshot>,
+    assets_sent: HashSet<String>,
+    /// файлы в очереди на отправку и номер следующего куска
+    assets_queue: VecDeque<(String, u32)>,
+    /// ключ - название события или шаблон из лимита
+    event_windows: HashMap<String, EventWindow>,
+}
+
+/// окно в одну секунду для лимита частоты событий
+#[derive(Debug)]
+struct EventWindow {
+    start: Instant,
+    count: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventRate {
+    Allowed,
+    /// первое превышение лимита в текущем окне
+    Flood,
+    Dropped,
+}
+
+/// скриншот, который собирается из `ScreenshotChunk`
+#[derive(Debug, Default)]
+struct Screenshot {
+    total: u32,
+    size: usize,
+    chunks: BTreeMap<u32, Vec<u8>>,
+}
+
+/// пакеты для игрока, у которого еще нет cef соединения
+#[derive(Debug)]
+pub struct Pending {
+    packets: Vec<Packet<'static>>,
+    size: usize,
+    /// `max_pending_size` из `cef.toml`
+    max_size: usize,
+    /// номера отслеживаемых пакетов, клиент продолжит с последнего
+    seq: u32,
+    screenshots: Vec<u32>,
+}
+
+#[derive(Debug)]
+pub enum ScreenshotChunk {
+    Pending,
+    Complete(Vec<u8>),
+    Failed,
 }
 
 impl Client {
@@ -23,10 +110,18 @@ impl Client {
             addr,
             peer,
             state: State::Connecting,
+            version: 0,
+            seq: 0,
+            delivered: BTreeSet::new(),
+            info: None,
+            outgoing: Vec::new(),
+            screenshots: HashMap::new(),
+            assets_sent: HashSet::new(),
+            assets_queue: VecDeque::new(),
+            event_windows: HashMap::new(),
         }
     }
 
-    #[allow(dead_code)]
     pub fn is_connected(&self) -> bool {
         self.state == State::Connected
     }
@@ -46,4 +141,299 @@ impl Client {
     pub fn peer(&self) -> PeerId {
         self.peer
     }
+
+    pub fn info(&self) -> Option<&ClientInfo> {
+        self.info.as_ref()
+    }
+
+    pub fn set_info(&mut self, info: ClientInfo) {
+        self.info = Some(info);
+    }
+
+    pub fn set_version(&mut self, version: i32) {
+        self.version = version;
+    }
+
+    /// следующий номер пакета, если клиент умеет подтверждать доставку
+    pub fn next_seq(&mut self) -> Option<u32> {
+        if self.version < ACK_MIN_VERSION {
+            return None;
+        }
+
+        self.seq = self.seq.wrapping_add(1).max(1);
+        Some(self.seq)
+    }
+
+    /// возвращает `false` на чужие и повторные подтверждения
+    pub fn ack(&mut self, seq: u32) -> bool {
+        if seq == 0 || seq > self.seq || !self.delivered.insert(seq) {
+            return false;
+        }
+
+        if self.delivered.len() > DELIVERED_HISTORY {
+            self.delivered.pop_first();
+        }
+
+        true
+    }
+
+    pub fn is_delivered(&self, seq: u32) -> bool {
+        self.delivered.contains(&seq)
+    }
+
+    pub fn supports_batch(&self) -> bool {
+        self.version >= BATCH_MIN_VERSION
+    }
+
+    pub fn supports_typed_events(&self) -> bool {
+        self.version >= TYPED_EVENTS_MIN_VERSION
+    }
+
+    /// пакет уйдет клиенту в конце тика
+    pub fn queue(&mut self, packet: Packet<'static>) {
+        self.outgoing.push(packet);
+    }
+
+    pub fn take_outgoing(&mut self) -> Vec<Packet<'static>> {
+        std::mem::take(&mut self.outgoing)
+    }
+
+    /// отправляет накопленное до входа, старый клиент не поймет `seq`, поэтому он убирается
+    pub fn queue_pending(&mut self, pending: Pending) {
+        let ack = self.version >= ACK_MIN_VERSION;
+
+        let mut stripped = Vec::new();
+
+        for mut packet in pending.packets {
+            match packet.seq {
+                Some(seq) if !ack => {
+                    stripped.push(seq);
+                    packet.seq = None;
+                }
+                _ => (),
+            }
+
+            self.queue(packet);
+        }
+
+        if !stripped.is_empty() {
+            log::warn!(
+                "player {} is too old to acknowledge packets, tracked seqs {:?} won't be delivered",
+                self.id,
+                stripped
+            );
+        }
+
+        if ack {
+            self.seq = self.seq.max(pending.seq);
+        }
+
+        pending
+            .screenshots
+            .into_iter()
+            .for_each(|request_id| self.expect_screenshot(request_id));
+    }
+
+    pub fn count_event(&mut self, key: &str, per_second: u32) -> EventRate {
+        self.count_event_at(key, per_second, Instant::now())
+    }
+
+    fn count_event_at(&mut self, key: &str, per_second: u32, now: Instant) -> EventRate {
+        if !self.event_windows.contains_key(key) {
+            let window = EventWindow {
+                start: now,
+                count: 0,
+            };
+
+            self.event_windows.insert(key.to_owned(), window);
+        }
+
+        let Some(window) = self.event_windows.get_mut(key) else {
+            return EventRate::Allowed;
+        };
+
+        if now.duration_since(window.start) >= Duration::from_secs(1) {
+            window.start = now;
+            window.count = 0;
+        }
+
+        window.count = window.count.saturating_add(1);
+
+        match window.count.checked_sub(per_second) {
+            None | Some(0) => EventRate::Allowed,
+            Some(1) => EventRate::Flood,
+            Some(_) => EventRate::Dropped,
+        }
+    }
+
+    /// каждый файл отдается клиенту только один раз за соединение
+    pub fn mark_asset_sent(&mut self, path: &str) -> bool {
+        self.assets_sent.insert(path.to_owned())
+    }
+
+    pub fn queue_asset(&mut self, path: String) {
+        self.assets_queue.push_back((path, 0));
+    }
+
+    /// куски читаются из общих `Assets` по мере отправки, а не копируются в очередь сразу
+    pub fn queue_asset_chunks(&mut self, assets: &Assets, mut count: usize) {
+        while count > 0
+            && let Some((path, index)) = self.assets_queue.front_mut()
+        {
+            let Some(chunk) = assets.chunk(path, *index) else {
+                self.assets_queue.pop_front();
+                continue;
+            };
+
+            *index += 1;
+            count -= 1;
+
+            match Packet::try_from(chunk) {
+                Ok(packet) => self.outgoing.push(Packet {
+                    packet_id: packet.packet_id,
+                    bytes: Cow::Owned(packet.bytes.into_owned()),
+                    seq: None,
+                }),
+
+                Err(_) => log::error!("couldn't serialize an asset chunk for player {}", self.id),
+            }
+        }
+    }
+
+    /// куски принимаются только для запрошенных скриншотов
+    pub fn expect_screenshot(&mut self, request_id: u32) {
+        self.screenshots.insert(request_id, Screenshot::default());
+    }
+
+    /// `total = 0` - клиент не смог сделать скриншот
+    pub fn push_screenshot_chunk(
+        &mut self, request_id: u32, index: u32, total: u32, data: &[u8],
+    ) -> ScreenshotChunk {
+        let Some(screenshot) = self.screenshots.get_mut(&request_id) else {
+            return ScreenshotChunk::Pending;
+        };
+
+        if screenshot.total == 0 {
+            screenshot.total = total;
+        }
+
+        screenshot.size += data.len();
+
+        let valid = total != 0
+            && total == screenshot.total
+            && index < total
+            && screenshot.size <= MAX_SCREENSHOT_SIZE
+            && screenshot.chunks.insert(index, data.to_vec()).is_none();
+
+        if !valid {
+            self.screenshots.remove(&request_id);
+            return ScreenshotChunk::Failed;
+        }
+
+        if screenshot.chunks.len() < total as usize {
+            return ScreenshotChunk::Pending;
+        }
+
+        self.screenshots
+            .remove(&request_id)
+            .map(|screenshot| {
+                ScreenshotChunk::Complete(screenshot.chunks.into_values().flatten().collect())
+            })
+            .unwrap_or(ScreenshotChunk::Failed)
+    }
+}
+
+impl Pending {
+    pub fn new(max_size: usize) -> Pending {
+        Pending {
+            packets: Vec::new(),
+            size: 0,
+            max_size,
+            seq: 0,
+            screenshots: Vec::new(),
+        }
+    }
+
+    /// `false`, если очередь переполнена
+    pub fn push(&mut self, packet: Packet<'static>) -> bool {
+        let size = packet.get_size();
+
+        if self.size + size > self.max_size {
+            return false;
+        }
+
+        self.size += size;
+        self.packets.push(packet);
+
+        true
+    }
+
+    /// версия клиента еще неизвестна, номер выдается наперед
+    pub fn next_seq(&mut self) -> u32 {
+        self.seq = self.seq.wrapping_add(1).max(1);
+        self.seq
+    }
+
+    pub fn expect_screenshot(&mut self, request_id: u32) {
+        self.screenshots.push(request_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client() -> Client {
+        Client::new(
+            0,
+            PeerId::default(),
+            SocketAddr::from(([127, 0, 0, 1], 7779)),
+        )
+    }
+
+    #[test]
+    fn event_rate_allows_then_floods_then_drops() {
+        let mut client = client();
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(client.count_event_at("chat", 3, now), EventRate::Allowed);
+        }
+
+        assert_eq!(client.count_event_at("chat", 3, now), EventRate::Flood);
+        assert_eq!(client.count_event_at("chat", 3, now), EventRate::Dropped);
+    }
+
+    #[test]
+    fn event_rate_window_resets_after_a_second() {
+        let mut client = client();
+        let now = Instant::now();
+
+        assert_eq!(client.count_event_at("chat", 1, now), EventRate::Allowed);
+        assert_eq!(client.count_event_at("chat", 1, now), EventRate::Flood);
+
+        let later = now + Duration::from_millis(999);
+        assert_eq!(client.count_event_at("chat", 1, later), EventRate::Dropped);
+
+        let next = now + Duration::from_secs(1);
+        assert_eq!(client.count_event_at("chat", 1, next), EventRate::Allowed);
+        assert_eq!(client.count_event_at("chat", 1, next), EventRate::Flood);
+    }
+
+    #[test]
+    fn event_rate_is_counted_per_key() {
+        let mut client = client();
+        let now = Instant::now();
+
+        assert_eq!(
+            client.count_event_at("inventory:*", 1, now),
+            EventRate::Allowed
+        );
+        assert_eq!(client.count_event_at("*", 1, now), EventRate::Allowed);
+        assert_eq!(
+            client.count_event_at("inventory:*", 1, now),
+            EventRate::Flood
+        );
+        assert_eq!(client.count_event_at("*", 1, now), EventRate::Flood);
+    }
 }
@@ -0,0 +1,542 @@
+use log::LevelFilter;
+use messages::Limits;
+use toml_edit::{DocumentMut, Item, TableLike};
+
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::logging::LogConfig;
+
+const CONFIG_PATH: &str = "cef.toml";
+const SERVER_CFG_PATH: &str = "server.cfg";
+
+const DEFAULT_PORT: u16 = 7777;
+const DEFAULT_PORT_OFFSET: u16 = 2;
+const DEFAULT_INIT_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_MAX_PENDING_SIZE: usize = 1024 * 1024;
+
+/// разделы `cef.toml` и их ключи, все остальное считается опечаткой
+const KNOWN_KEYS: &[(&str, &[&str])] = &[
+    (
+        "server",
+        &[
+            "bind",
+            "port",
+            "port_offset",
+            "init_timeout",
+            "allow_js",
+            "restore_browsers",
+            "assets_dir",
+        ],
+    ),
+    (
+        "limits",
+        &[
+            "max_packet_size",
+            "max_event_name",
+            "max_arguments",
+            "max_url",
+            "max_string",
+            "max_depth",
+            "max_pending_size",
+        ],
+    ),
+    ("tls", &["certificate", "key"]),
+    (
+        "log",
+        &["level", "filters", "file", "max_size", "max_files", "json"],
+    ),
+];
+
+/// настройки плагина из `cef.toml`, `bind` и `port` могут браться из `server.cfg`
+pub struct Config {
+    pub addr: SocketAddr,
+    /// сколько ждать подключения игрока к cef серверу после входа
+    pub init_timeout: Duration,
+    pub allow_js: bool,
+    pub restore_browsers: bool,
+    pub assets_dir: PathBuf,
+    pub limits: Limits,
+    /// предел очереди пакетов для игрока, который еще не подключился к cef серверу
+    pub max_pending_size: usize,
+    /// сертификат и ключ в PEM, без них генерируется самоподписанный
+    pub tls: Option<(PathBuf, PathBuf)>,
+    pub log: LogConfig,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Parse(toml_edit::TomlError),
+    UnknownKey(String),
+    Value { key: String, expected: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "couldn't read {}: {}", CONFIG_PATH, err),
+            ConfigError::Parse(err) => write!(f, "couldn't parse {}: {}", CONFIG_PATH, err),
+            ConfigError::UnknownKey(key) => write!(f, "{}: unknown key `{}`", CONFIG_PATH, key),
+            ConfigError::Value { key, expected } => {
+                write!(f, "{}: `{}` should be {}", CONFIG_PATH, key, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// ошибочные значения заменяются значениями по умолчанию, ошибки возвращаются,
+    /// чтобы вывести их после запуска логгера
+    pub fn load() -> (Config, Vec<ConfigError>) {
+        let server_cfg = std::fs::read_to_string(SERVER_CFG_PATH).unwrap_or_default();
+
+        match std::fs::read_to_string(CONFIG_PATH) {
+            Ok(text) => Config::parse(Some(&text), &server_cfg),
+
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                Config::parse(None, &server_cfg)
+            }
+
+            Err(err) => {
+                let (config, mut errors) = Config::parse(None, &server_cfg);
+                errors.insert(0, ConfigError::Io(err));
+                (config, errors)
+            }
+        }
+    }
+
+    /// `cef_toml` - содержимое `cef.toml`, `None`, если файла нет
+    fn parse(cef_toml: Option<&str>, server_cfg: &str) -> (Config, Vec<ConfigError>) {
+        let mut reader = Reader::default();
+
+        let document = cef_toml.and_then(|text| {
+            text.parse::<DocumentMut>()
+                .map_err(|err| reader.errors.push(ConfigError::Parse(err)))
+                .ok()
+        });
+
+        let root = document
+            .as_ref()
+            .map(|document| document.as_table() as &dyn TableLike);
+
+        reader.check_keys(root);
+
+        let config = Config {
+            addr: reader.addr(root, server_cfg),
+            init_timeout: reader.init_timeout(root),
+            allow_js: reader.bool(root, "server", "allow_js").unwrap_or(true),
+            restore_browsers: reader
+                .bool(root, "server", "restore_browsers")
+                .unwrap_or(false),
+            assets_dir: reader
+                .string(root, "server", "assets_dir")
+                .unwrap_or_else(|| "cef_assets".into())
+                .into(),
+            limits: reader.limits(root),
+            max_pending_size: reader.limit(root, "max_pending_size", DEFAULT_MAX_PENDING_SIZE),
+            tls: reader.tls(root),
+            log: reader.log(root),
+        };
+
+        (config, reader.errors)
+    }
+}
+
+#[derive(Default)]
+struct Reader {
+    errors: Vec<ConfigError>,
+}
+
+impl Reader {
+    fn check_keys(&mut self, root: Option<&dyn TableLike>) {
+        let Some(root) = root else {
+            return;
+        };
+
+        for (name, item) in root.iter() {
+            let Some(&(_, known)) = KNOWN_KEYS.iter().find(|&&(section, _)| section == name) else {
+                self.errors.push(ConfigError::UnknownKey(name.to_owned()));
+                continue;
+            };
+
+            let Some(section) = item.as_table_like() else {
+                self.invalid(name, "a table");
+                continue;
+            };
+
+            for (key, _) in section.iter() {
+                if !known.contains(&key) {
+                    self.errors
+                        .push(ConfigError::UnknownKey(format!("{}.{}", name, key)));
+                }
+            }
+        }
+    }
+
+    /// `None`, если ключа нет или у него неверное значение
+    fn value<T>(
+        &mut self, root: Option<&dyn TableLike>, section: &str, key: &str, expected: &str,
+        convert: impl FnOnce(&Item) -> Option<T>,
+    ) -> Option<T> {
+        let item = root?.get(section)?.as_table_like()?.get(key)?;
+        let value = convert(item);
+
+        if value.is_none() {
+            self.invalid(&format!("{}.{}", section, key), expected);
+        }
+
+        value
+    }
+
+    fn bool(&mut self, root: Option<&dyn TableLike>, section: &str, key: &str) -> Option<bool> {
+        self.value(root, section, key, "true or false", Item::as_bool)
+    }
+
+    fn string(&mut self, root: Option<&dyn TableLike>, section: &str, key: &str) -> Option<String> {
+        self.value(root, section, key, "a string", |item| {
+            item.as_str().map(str::to_owned)
+        })
+    }
+
+    fn integer<T: TryFrom<i64>>(
+        &mut self, root: Option<&dyn TableLike>, section: &str, key: &str, expected: &str,
+    ) -> Option<T> {
+        self.value(root, section, key, expected, |item| {
+            item.as_integer().and_then(|value| T::try_from(value).ok())
+        })
+    }
+
+    fn parsed<T: FromStr>(
+        &mut self, root: Option<&dyn TableLike>, section: &str, key: &str, expected: &str,
+    ) -> Option<T> {
+        self.value(root, section, key, expected, |item| {
+            item.as_str().and_then(|value| value.parse().ok())
+        })
+    }
+
+    fn invalid(&mut self, key: &str, expected: &str) {
+        self.errors.push(ConfigError::Value {
+            key: key.to_owned(),
+            expected: expected.to_owned(),
+        });
+    }
+
+    fn addr(&mut self, root: Option<&dyn TableLike>, server_cfg: &str) -> SocketAddr {
+        let bind = self
+            .parsed(root, "server", "bind", "an IP address")
+            .or_else(|| server_cfg_field(server_cfg, "bind"))
+            .unwrap_or(IpAddr::from([0, 0, 0, 0]));
+
+        let port = self.value(root, "server", "port", "a port from 1 to 65535", |item| {
+            item.as_integer()
+                .and_then(|port| u16::try_from(port).ok())
+                .filter(|&port| port != 0)
+        });
+
+        let offset = self.integer(root, "server", "port_offset", "an integer from 0 to 65535");
+
+        // явный порт главнее смещения от порта samp сервера
+        let port = port.unwrap_or_else(|| {
+            let samp_port = server_cfg_field(server_cfg, "port").unwrap_or(DEFAULT_PORT);
+
+            samp_port
+                .checked_add(offset.unwrap_or(DEFAULT_PORT_OFFSET))
+                .unwrap_or_else(|| {
+                    self.invalid("server.port_offset", "small enough to fit the port");
+                    samp_port.saturating_add(DEFAULT_PORT_OFFSET)
+                })
+        });
+
+        SocketAddr::from((bind, port))
+    }
+
+    fn init_timeout(&mut self, root: Option<&dyn TableLike>) -> Duration {
+        self.value(
+            root,
+            "server",
+            "init_timeout",
+            "a positive number of seconds",
+            |item| {
+                item.as_float()
+                    .or(item.as_integer().map(|value| value as f64))
+                    .filter(|&secs| secs > 0.0)
+                    .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+            },
+        )
+        .unwrap_or(DEFAULT_INIT_TIMEOUT)
+    }
+
+    /// ключ из `[limits]`, 0 и отрицательные числа - ошибка
+    fn limit(&mut self, root: Option<&dyn TableLike>, key: &str, default: usize) -> usize {
+        self.value(root, "limits", key, "a positive integer", |item| {
+            item.as_integer()
+                .and_then(|value| usize::try_from(value).ok())
+                .filter(|&value| value != 0)
+        })
+        .unwrap_or(default)
+    }
+
+    fn limits(&mut self, root: Option<&dyn TableLike>) -> Limits {
+        let default = Limits::default();
+        let mut limit = |key, default| self.limit(root, key, default);
+
+        Limits {
+            max_packet_size: limit("max_packet_size", default.max_packet_size),
+            max_event_name: limit("max_event_name", default.max_event_name),
+            max_arguments: limit("max_arguments", default.max_arguments),
+            max_url: limit("max_url", default.max_url),
+            max_string: limit("max_string", default.max_string),
+            max_depth: limit("max_depth", default.max_depth),
+        }
+    }
+
+    fn tls(&mut self, root: Option<&dyn TableLike>) -> Option<(PathBuf, PathBuf)> {
+        let certificate = self.string(root, "tls", "certificate");
+        let key = self.string(root, "tls", "key");
+
+        match (certificate, key) {
+            (Some(certificate), Some(key)) => Some((certificate.into(), key.into())),
+            (None, None) => None,
+            (None, Some(_)) => {
+                self.invalid("tls.certificate", "set together with `tls.key`");
+                None
+            }
+            (Some(_), None) => {
+                self.invalid("tls.key", "set together with `tls.certificate`");
+                None
+            }
+        }
+    }
+
+    fn log(&mut self, root: Option<&dyn TableLike>) -> LogConfig {
+        let levels = "one of off, error, warn, info, debug or trace";
+        let default = LogConfig::default();
+
+        let filters = self
+            .value(
+                root,
+                "log",
+                "filters",
+                "a table of module = level",
+                |item| {
+                    item.as_table_like()?
+                        .iter()
+                        .map(|(module, level)| {
+                            Some((module.to_owned(), level.as_str()?.parse().ok()?))
+                        })
+                        .collect::<Option<Vec<(String, LevelFilter)>>>()
+                },
+            )
+            .unwrap_or_default();
+
+        LogConfig {
+            level: self
+                .parsed(root, "log", "level", levels)
+                .unwrap_or(default.level),
+            filters,
+            file: self.string(root, "log", "file").map(PathBuf::from),
+            max_size: self
+                .integer(root, "log", "max_size", "a non-negative integer")
+                .unwrap_or(default.max_size),
+            max_files: self
+                .integer(root, "log", "max_files", "a non-negative integer")
+                .unwrap_or(default.max_files),
+            json: self.bool(root, "log", "json").unwrap_or(default.json),
+        }
+    }
+}
+
+/// значение строки `<field> <value>` из `server.cfg`
+fn server_cfg_field<F: FromStr>(server_cfg: &str, field: &str) -> Option<F> {
+    server_cfg
+        .lines()
+        .find_map(|line| {
+            let mut parts = line.split_whitespace();
+            (parts.next() == Some(field))
+                .then(|| parts.next())
+                .flatten()
+        })
+        .and_then(|value| value.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(cef_toml: &str) -> Vec<String> {
+        let (_, errors) = Config::parse(Some(cef_toml), "");
+        errors.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn defaults_without_files() {
+        let (config, errors) = Config::parse(None, "");
+
+        assert!(errors.is_empty());
+        assert_eq!(config.addr, SocketAddr::from(([0, 0, 0, 0], 7779)));
+        assert_eq!(config.init_timeout, DEFAULT_INIT_TIMEOUT);
+        assert!(config.allow_js);
+        assert!(!config.restore_browsers);
+        assert_eq!(config.limits, Limits::default());
+        assert_eq!(config.max_pending_size, DEFAULT_MAX_PENDING_SIZE);
+        assert!(config.tls.is_none());
+    }
+
+    #[test]
+    fn reads_all_sections() {
+        let (config, errors) = Config::parse(
+            Some(
+                r#"
+                [server]
+                bind = "127.0.0.1"
+                port = 9000
+                init_timeout = 2.5
+                allow_js = false
+                restore_browsers = true
+                assets_dir = "ui"
+
+                [limits]
+                max_url = 100
+                max_pending_size = 2048
+
+                [tls]
+                certificate = "cert.pem"
+                key = "key.pem"
+
+                [log]
+                level = "debug"
+                json = true
+
+                [log.filters]
+                "server::client" = "trace"
+                "#,
+            ),
+            "",
+        );
+
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(config.addr, SocketAddr::from(([127, 0, 0, 1], 9000)));
+        assert_eq!(config.init_timeout, Duration::from_millis(2500));
+        assert!(!config.allow_js);
+        assert!(config.restore_browsers);
+        assert_eq!(config.assets_dir, PathBuf::from("ui"));
+        assert_eq!(config.limits.max_url, 100);
+        assert_eq!(config.max_pending_size, 2048);
+        assert_eq!(
+            config.tls,
+            Some((PathBuf::from("cert.pem"), PathBuf::from("key.pem")))
+        );
+        assert_eq!(config.log.level, LevelFilter::Debug);
+        assert_eq!(
+            config.log.filters,
+            [("server::client".to_owned(), LevelFilter::Trace)]
+        );
+        assert!(config.log.json);
+    }
+
+    #[test]
+    fn falls_back_to_server_cfg() {
+        let server_cfg = "hostname test\nbind 10.0.0.1\nport 8000\n";
+
+        let (config, errors) = Config::parse(None, server_cfg);
+        assert!(errors.is_empty());
+        assert_eq!(config.addr, SocketAddr::from(([10, 0, 0, 1], 8002)));
+
+        let (config, _) = Config::parse(Some("[server]\nport_offset = 5"), server_cfg);
+        assert_eq!(config.addr, SocketAddr::from(([10, 0, 0, 1], 8005)));
+
+        // явные значения главнее server.cfg
+        let (config, _) = Config::parse(
+            Some("[server]\nbind = \"127.0.0.1\"\nport = 7000"),
+            server_cfg,
+        );
+        assert_eq!(config.addr, SocketAddr::from(([127, 0, 0, 1], 7000)));
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert_eq!(
+            errors("[server]\nallow_jss = true\n\n[limit]\nmax_url = 1"),
+            [
+                "cef.toml: unknown key `server.allow_jss`",
+                "cef.toml: unknown key `limit`",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_types() {
+        let (config, errors) = Config::parse(
+            Some(
+                "[server]\nallow_js = \"no\"\nport = \"7000\"\ninit_timeout = -1\nbind = \"localhost\"",
+            ),
+            "",
+        );
+
+        let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+
+        assert_eq!(
+            errors,
+            [
+                "cef.toml: `server.bind` should be an IP address",
+                "cef.toml: `server.port` should be a port from 1 to 65535",
+                "cef.toml: `server.init_timeout` should be a positive number of seconds",
+                "cef.toml: `server.allow_js` should be true or false",
+            ]
+        );
+
+        // вместо ошибочных значений - значения по умолчанию
+        assert!(config.allow_js);
+        assert_eq!(config.addr, SocketAddr::from(([0, 0, 0, 0], 7779)));
+        assert_eq!(config.init_timeout, DEFAULT_INIT_TIMEOUT);
+    }
+
+    #[test]
+    fn rejects_non_positive_limits() {
+        let (config, errors) = Config::parse(
+            Some("[limits]\nmax_url = -1\nmax_string = 0\nmax_pending_size = -1024"),
+            "",
+        );
+
+        let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+
+        assert_eq!(
+            errors,
+            [
+                "cef.toml: `limits.max_url` should be a positive integer",
+                "cef.toml: `limits.max_string` should be a positive integer",
+                "cef.toml: `limits.max_pending_size` should be a positive integer",
+            ]
+        );
+
+        assert_eq!(config.limits, Limits::default());
+        assert_eq!(config.max_pending_size, DEFAULT_MAX_PENDING_SIZE);
+    }
+
+    #[test]
+    fn rejects_bad_sections_and_values() {
+        assert_eq!(
+            errors("server = 1\n\n[tls]\nkey = \"key.pem\"\n\n[log]\nlevel = \"verbose\""),
+            [
+                "cef.toml: `server` should be a table",
+                "cef.toml: `tls.certificate` should be set together with `tls.key`",
+                "cef.toml: `log.level` should be one of off, error, warn, info, debug or trace",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        let (config, errors) = Config::parse(Some("[server\nport = 1"), "port 8000");
+
+        assert!(matches!(errors.as_slice(), [ConfigError::Parse(_)]));
+        assert!(errors[0].to_string().starts_with("couldn't parse cef.toml"));
+        assert_eq!(config.addr.port(), 8002);
+    }
+}
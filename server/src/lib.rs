@@ -1,58 +1,161 @@
 use std::collections::HashMap;
-use std::net::{IpAddr, SocketAddr};
+use std::net::IpAddr;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use crossbeam_channel::Receiver;
-use log::{info, trace};
-use messages::packets::EventValue;
-// use simplelog::{CombinedLogger, LevelFilter, TermLogger, TerminalMode};
+use log::{info, trace, warn};
+use messages::packets::{Anchor, EventValue, NavigationAction};
+use network::DisconnectReason;
 
 use samp::amx::AmxIdent;
 use samp::args::Args;
 use samp::prelude::*;
 use samp::{exec_public, initialize_plugin, native};
 
+mod assets;
+mod browser;
 mod client;
+mod config;
+mod logging;
 mod server;
-mod utils;
+mod signature;
 
-use crate::server::Server;
+use crate::assets::Assets;
+use crate::config::Config;
+use crate::server::{SendStatus, Server};
+use crate::signature::{ArgType, TypedValue};
 
-const INIT_TIMEOUT: Duration = Duration::from_secs(5);
-const PORT_OFFSET: u16 = 2;
+/// колбек подписки возвращает это значение, чтобы остальные подписчики не получили событие
+const EVENT_BREAK: i32 = -1;
 
 pub enum Event {
     EmitEvent {
         player_id: i32,
+        browser_id: Option<u32>,
         event: String,
         arguments: String,
+        /// `None` у старых клиентов, которые присылают только строку
+        values: Option<Vec<EventValue<'static>>>,
     },
     PlayerConnected(i32),
+    ClientInfo(i32),
     BrowserCreated {
         player_id: i32,
         browser_id: u32,
         code: i32,
     },
+    BrowserLoaded {
+        player_id: i32,
+        browser_id: u32,
+        url: String,
+        code: i32,
+    },
+    BrowserLoadError {
+        player_id: i32,
+        browser_id: u32,
+        code: i32,
+        text: String,
+        url: String,
+    },
+    BrowserDestroyed {
+        player_id: i32,
+        browser_id: u32,
+    },
+    RendererCrashed {
+        player_id: i32,
+        browser_id: u32,
+        status: i32,
+    },
+    LoadingStateChanged {
+        player_id: i32,
+        browser_id: u32,
+        is_loading: bool,
+        can_go_back: bool,
+        can_go_forward: bool,
+    },
+    PacketDelivered {
+        player_id: i32,
+        seq: u32,
+    },
+    JavaScriptResult {
+        player_id: i32,
+        browser_id: u32,
+        request_id: u32,
+        success: bool,
+        result: String,
+    },
+    AssetsReady {
+        player_id: i32,
+        success: bool,
+    },
+    Screenshot {
+        player_id: i32,
+        browser_id: u32,
+        request_id: u32,
+        /// путь относительно `scriptfiles`, `None` если скриншот не удался
+        path: Option<String>,
+    },
+    BrowsersRestored {
+        player_id: i32,
+        count: u32,
+    },
+    EventFlood {
+        player_id: i32,
+        event: String,
+    },
+    Disconnected {
+        player_id: i32,
+        reason: DisconnectReason,
+    },
+    BrowserFocusChanged {
+        player_id: i32,
+        browser_id: u32,
+        focused: bool,
+    },
+    BrowserVisibilityChanged {
+        player_id: i32,
+        browser_id: u32,
+        hidden: bool,
+    },
+}
+
+#[derive(Clone)]
+struct Subscriber {
+    ident: AmxIdent,
+    callback: String,
+    /// `None` - аргументы передаются одной строкой
+    signature: Option<Vec<ArgType>>,
 }
 
 struct CefPlugin {
     server: Arc<Mutex<Server>>,
-    events: HashMap<String, (AmxIdent, String)>,
+    /// ключ - название события или шаблон вида `inventory:*`, подписчики в порядке подписки
+    events: HashMap<String, Vec<Subscriber>>,
     event_rx: Receiver<Event>,
     amx_list: Vec<AmxIdent>,
     await_connect: HashMap<i32, Instant>,
+    init_timeout: Duration,
     ips: HashMap<i32, IpAddr>,
+    allow_js: bool,
+    js_callbacks: HashMap<u32, (i32, AmxIdent, String)>,
+    js_request_id: u32,
+    screenshot_callbacks: HashMap<u32, (i32, AmxIdent, String)>,
+    screenshot_request_id: u32,
 }
 
 impl CefPlugin {
-    fn new() -> Self {
-        let ip: IpAddr =
-            crate::utils::parse_config_field("bind").unwrap_or_else(|| "0.0.0.0".parse().unwrap());
-
-        let port = crate::utils::parse_config_field("port").unwrap_or(7777);
-        let addr = SocketAddr::from((ip, port + PORT_OFFSET));
-        let server = Server::new(addr);
+    fn new(config: Config) -> Self {
+        let addr = config.addr;
+        let assets = Assets::load(&config.assets_dir);
+        let server = Server::new(
+            addr,
+            config.limits,
+            config.max_pending_size,
+            assets,
+            config.restore_browsers,
+            config.tls,
+        );
 
         info!("Bind CEF server on {:?}", addr);
 
@@ -67,7 +170,13 @@ impl CefPlugin {
             events: HashMap::new(),
             amx_list: Vec::new(),
             await_connect: HashMap::new(),
+            init_timeout: config.init_timeout,
             ips: HashMap::new(),
+            allow_js: config.allow_js,
+            js_callbacks: HashMap::new(),
+            js_request_id: 0,
+            screenshot_callbacks: HashMap::new(),
+            screenshot_request_id: 0,
         }
     }
 
@@ -88,6 +197,11 @@ impl CefPlugin {
             }
 
             self.add_to_await_list(player_id);
+        } else {
+            warn!(
+                "cef_on_player_connect: bad IP {} of {}",
+                player_ip, player_id
+            );
         }
 
         Ok(true)
@@ -105,6 +219,10 @@ impl CefPlugin {
         }
 
         self.remove_from_await_list(player_id);
+        self.js_callbacks
+            .retain(|_, (player, ..)| *player != player_id);
+        self.screenshot_callbacks
+            .retain(|_, (player, ..)| *player != player_id);
 
         Ok(true)
     }
@@ -113,118 +231,218 @@ impl CefPlugin {
     fn create_browser(
         &mut self, _: &Amx, player_id: i32, browser_id: i32, url: AmxString, hidden: bool,
         focused: bool,
-    ) -> AmxResult<bool> {
+    ) -> AmxResult<i32> {
         let mut server = self.server.lock().unwrap();
-        server.create_browser(player_id, browser_id, url.to_string(), hidden, focused);
+        let status = server.create_browser(player_id, browser_id, url.to_string(), hidden, focused);
 
-        Ok(true)
+        Ok(status as i32)
+    }
+
+    /// возвращает `seq` для `OnCefPacketDelivered` или 0
+    #[native(name = "cef_create_browser_tracked")]
+    fn create_browser_tracked(
+        &mut self, _: &Amx, player_id: i32, browser_id: i32, url: AmxString, hidden: bool,
+        focused: bool,
+    ) -> AmxResult<i32> {
+        let url = url.to_string();
+        let mut server = self.server.lock().unwrap();
+        let seq = server
+            .tracked(|server| server.create_browser(player_id, browser_id, url, hidden, focused));
+
+        Ok(seq as i32)
     }
 
     #[native(name = "cef_destroy_browser")]
-    fn destroy_browser(&mut self, _: &Amx, player_id: i32, browser_id: i32) -> AmxResult<bool> {
+    fn destroy_browser(&mut self, _: &Amx, player_id: i32, browser_id: i32) -> AmxResult<i32> {
         let mut server = self.server.lock().unwrap();
-        server.destroy_browser(player_id, browser_id);
+        let status = server.destroy_browser(player_id, browser_id);
 
-        Ok(true)
+        Ok(status as i32)
+    }
+
+    #[native(name = "cef_destroy_browser_tracked")]
+    fn destroy_browser_tracked(
+        &mut self, _: &Amx, player_id: i32, browser_id: i32,
+    ) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
+        let seq = server.tracked(|server| server.destroy_browser(player_id, browser_id));
+
+        Ok(seq as i32)
     }
 
     #[native(name = "cef_hide_browser")]
     fn hide_browser(
         &mut self, _: &Amx, player_id: i32, browser_id: i32, hide: bool,
-    ) -> AmxResult<bool> {
-        let server = self.server.lock().unwrap();
-        server.hide_browser(player_id, browser_id, hide);
+    ) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
+        let status = server.hide_browser(player_id, browser_id, hide);
 
-        Ok(true)
+        Ok(status as i32)
+    }
+
+    #[native(name = "cef_hide_browser_tracked")]
+    fn hide_browser_tracked(
+        &mut self, _: &Amx, player_id: i32, browser_id: i32, hide: bool,
+    ) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
+        let seq = server.tracked(|server| server.hide_browser(player_id, browser_id, hide));
+
+        Ok(seq as i32)
     }
 
     #[native(name = "cef_focus_browser")]
     fn browser_listen_events(
         &mut self, _: &Amx, player_id: i32, browser_id: i32, focused: bool,
-    ) -> AmxResult<bool> {
-        let server = self.server.lock().unwrap();
-        server.focus_browser(player_id, browser_id, focused);
+    ) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
+        let status = server.focus_browser(player_id, browser_id, focused);
 
-        Ok(true)
+        Ok(status as i32)
+    }
+
+    #[native(name = "cef_focus_browser_tracked")]
+    fn focus_browser_tracked(
+        &mut self, _: &Amx, player_id: i32, browser_id: i32, focused: bool,
+    ) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
+        let seq = server.tracked(|server| server.focus_browser(player_id, browser_id, focused));
+
+        Ok(seq as i32)
     }
 
     #[native(name = "cef_emit_event", raw)]
-    fn emit_event(&mut self, _: &Amx, args: Args) -> AmxResult<bool> {
+    fn emit_event(&mut self, _: &Amx, args: Args) -> AmxResult<i32> {
         if args.count() < 2 || !(args.count() - 2).is_multiple_of(2) {
             info!("cef_emit_event invalid count of arguments");
-            return Ok(false);
+            return Ok(SendStatus::Dropped as i32);
         }
 
-        let mut arguments = Vec::with_capacity((args.count() - 2) / 2);
-
         let player_id = args.get::<i32>(0).unwrap();
         let event_name = args.get::<AmxString>(1).unwrap().to_string();
+        let arguments = event_arguments(&args, 2);
 
-        let mut idx = 2;
-
-        loop {
-            if idx >= args.count() {
-                break;
-            }
-
-            if let Some(ty) = args.get::<Ref<i32>>(idx) {
-                idx += 1;
+        let mut server = self.server.lock().unwrap();
+        let status = server.emit_event(player_id, None, &event_name, arguments);
 
-                let arg = match *ty {
-                    0 => EventValue {
-                        string_value: Some(args.get::<AmxString>(idx).unwrap().to_string().into()),
-                        float_value: None,
-                        integer_value: None,
-                    },
+        Ok(status as i32)
+    }
 
-                    1 => EventValue {
-                        string_value: None,
-                        float_value: None,
-                        integer_value: Some(*args.get::<Ref<i32>>(idx).unwrap()),
-                    },
+    #[native(name = "cef_emit_event_tracked", raw)]
+    fn emit_event_tracked(&mut self, _: &Amx, args: Args) -> AmxResult<i32> {
+        if args.count() < 2 || !(args.count() - 2).is_multiple_of(2) {
+            info!("cef_emit_event_tracked invalid count of arguments");
+            return Ok(0);
+        }
 
-                    2 => EventValue {
-                        string_value: None,
-                        float_value: Some(*args.get::<Ref<f32>>(idx).unwrap()),
-                        integer_value: None,
-                    },
+        let player_id = args.get::<i32>(0).unwrap();
+        let event_name = args.get::<AmxString>(1).unwrap().to_string();
+        let arguments = event_arguments(&args, 2);
 
-                    _ => break,
-                };
+        let mut server = self.server.lock().unwrap();
+        let seq =
+            server.tracked(|server| server.emit_event(player_id, None, &event_name, arguments));
 
-                arguments.push(arg);
+        Ok(seq as i32)
+    }
 
-                idx += 1;
-            } else {
-                break;
-            }
+    #[native(name = "cef_emit_event_to_browser", raw)]
+    fn emit_event_to_browser(&mut self, _: &Amx, args: Args) -> AmxResult<i32> {
+        if args.count() < 3 || !(args.count() - 3).is_multiple_of(2) {
+            info!("cef_emit_event_to_browser invalid count of arguments");
+            return Ok(SendStatus::Dropped as i32);
         }
 
-        let server = self.server.lock().unwrap();
-        server.emit_event(player_id, &event_name, arguments);
+        let player_id = args.get::<i32>(0).unwrap();
+        let browser_id = args.get::<i32>(1).unwrap();
+        let event_name = args.get::<AmxString>(2).unwrap().to_string();
+        let arguments = event_arguments(&args, 3);
 
-        Ok(true)
+        let mut server = self.server.lock().unwrap();
+        let status = server.emit_event(player_id, Some(browser_id as u32), &event_name, arguments);
+
+        Ok(status as i32)
     }
 
     #[native(name = "cef_always_listen_keys")]
     fn block_input(
         &mut self, _: &Amx, player_id: i32, browser_id: i32, listen: bool,
-    ) -> AmxResult<bool> {
-        let server = self.server.lock().unwrap();
-        server.always_listen_keys(player_id, browser_id, listen);
+    ) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
+        let status = server.always_listen_keys(player_id, browser_id, listen);
 
-        Ok(true)
+        Ok(status as i32)
     }
 
     #[native(name = "cef_subscribe")]
     fn subscribe(
         &mut self, amx: &Amx, event_name: AmxString, callback: AmxString,
+    ) -> AmxResult<bool> {
+        let subscriber = Subscriber {
+            ident: amx.ident(),
+            callback: callback.to_string(),
+            signature: None,
+        };
+
+        Ok(self.add_subscriber(event_name.to_string(), subscriber))
+    }
+
+    #[native(name = "cef_subscribe_typed")]
+    fn subscribe_typed(
+        &mut self, amx: &Amx, event_name: AmxString, callback: AmxString, signature: AmxString,
+    ) -> AmxResult<bool> {
+        let event_name = event_name.to_string();
+        let signature = signature.to_string();
+
+        let Some(signature) = crate::signature::parse(&signature) else {
+            info!(
+                "cef_subscribe_typed({}) invalid signature {:?}",
+                event_name, signature
+            );
+
+            return Ok(false);
+        };
+
+        let subscriber = Subscriber {
+            ident: amx.ident(),
+            callback: callback.to_string(),
+            signature: Some(signature),
+        };
+
+        Ok(self.add_subscriber(event_name, subscriber))
+    }
+
+    #[native(name = "cef_unsubscribe")]
+    fn unsubscribe(
+        &mut self, amx: &Amx, event_name: AmxString, callback: AmxString,
     ) -> AmxResult<bool> {
         let ident = amx.ident();
         let event_name = event_name.to_string();
         let callback = callback.to_string();
 
-        self.events.insert(event_name, (ident, callback));
+        let Some(subscribers) = self.events.get_mut(&event_name) else {
+            return Ok(false);
+        };
+
+        let count = subscribers.len();
+        subscribers.retain(|sub| sub.ident != ident || sub.callback != callback);
+        let removed = subscribers.len() != count;
+
+        if subscribers.is_empty() {
+            self.events.remove(&event_name);
+        }
+
+        Ok(removed)
+    }
+
+    #[native(name = "cef_set_event_rate_limit")]
+    fn set_event_rate_limit(
+        &mut self, _: &Amx, event_name: AmxString, per_second: i32, disconnect: bool,
+    ) -> AmxResult<bool> {
+        let event_name = event_name.to_string();
+        let per_second = per_second.max(0) as u32;
+
+        let mut server = self.server.lock().unwrap();
+        server.set_event_rate_limit(event_name, per_second, disconnect);
 
         Ok(true)
     }
@@ -240,179 +458,1079 @@ impl CefPlugin {
     fn create_external_browser(
         &mut self, _: &Amx, player_id: i32, browser_id: i32, texture: AmxString, url: AmxString,
         scale: i32,
-    ) -> AmxResult<bool> {
+    ) -> AmxResult<i32> {
         let texture = texture.to_string();
         let url = url.to_string();
 
-        let server = self.server.lock().unwrap();
-        server.create_external_browser(player_id, browser_id, texture, url, scale);
+        let mut server = self.server.lock().unwrap();
+        let status = server.create_external_browser(player_id, browser_id, texture, url, scale);
 
-        Ok(true)
+        Ok(status as i32)
     }
 
     #[native(name = "cef_append_to_object")]
     fn append_to_object(
         &mut self, _: &Amx, player_id: i32, browser_id: i32, object_id: i32,
-    ) -> AmxResult<bool> {
-        let server = self.server.lock().unwrap();
-        server.append_to_object(player_id, browser_id, object_id);
-        Ok(true)
+    ) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
+        let status = server.append_to_object(player_id, browser_id, object_id);
+        Ok(status as i32)
     }
 
     #[native(name = "cef_remove_from_object")]
     fn remove_from_object(
         &mut self, _: &Amx, player_id: i32, browser_id: i32, object_id: i32,
-    ) -> AmxResult<bool> {
-        let server = self.server.lock().unwrap();
-        server.remove_from_object(player_id, browser_id, object_id);
-        Ok(true)
+    ) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
+        let status = server.remove_from_object(player_id, browser_id, object_id);
+        Ok(status as i32)
     }
 
     #[native(name = "cef_toggle_dev_tools")]
     fn toggle_dev_tools(
         &mut self, _: &Amx, player_id: i32, browser_id: i32, enabled: bool,
-    ) -> AmxResult<bool> {
-        let server = self.server.lock().unwrap();
-        server.toggle_dev_tools(player_id, browser_id, enabled);
-        Ok(true)
+    ) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
+        let status = server.toggle_dev_tools(player_id, browser_id, enabled);
+        Ok(status as i32)
     }
 
     #[native(name = "cef_set_audio_settings")]
     fn set_audio_settings(
         &mut self, _: &Amx, player_id: i32, browser_id: u32, max_distance: f32,
         reference_distance: f32,
-    ) -> AmxResult<bool> {
-        let server = self.server.lock().unwrap();
-        server.set_audio_settings(player_id, browser_id, max_distance, reference_distance);
-        Ok(true)
+    ) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
+        let status =
+            server.set_audio_settings(player_id, browser_id, max_distance, reference_distance);
+        Ok(status as i32)
     }
 
     #[native(name = "cef_load_url")]
     fn load_url(
         &mut self, _: &Amx, player_id: i32, browser_id: u32, url: AmxString,
-    ) -> AmxResult<bool> {
+    ) -> AmxResult<i32> {
         let url = url.to_string();
-        let server = self.server.lock().unwrap();
+        let mut server = self.server.lock().unwrap();
 
-        server.load_url(player_id, browser_id, url);
+        let status = server.load_url(player_id, browser_id, url);
 
-        Ok(true)
+        Ok(status as i32)
     }
 
-    // utils
-    fn notify_timeout(&mut self) {
-        let mut keys = Vec::new();
+    #[native(name = "cef_load_url_tracked")]
+    fn load_url_tracked(
+        &mut self, _: &Amx, player_id: i32, browser_id: u32, url: AmxString,
+    ) -> AmxResult<i32> {
+        let url = url.to_string();
+        let mut server = self.server.lock().unwrap();
+        let seq = server.tracked(|server| server.load_url(player_id, browser_id, url));
 
-        for (&player_id, timing) in self.await_connect.iter() {
-            if timing.elapsed() >= INIT_TIMEOUT {
-                keys.push(player_id);
-                self.notify_connect(player_id, false);
-            }
-        }
+        Ok(seq as i32)
+    }
 
-        keys.into_iter().for_each(|player_id| {
-            let result = self.remove_from_await_list(player_id);
+    #[allow(clippy::too_many_arguments)]
+    #[native(name = "cef_set_browser_rect")]
+    fn set_browser_rect(
+        &mut self, _: &Amx, player_id: i32, browser_id: u32, x: f32, y: f32, width: f32,
+        height: f32, anchor: i32, percent: bool,
+    ) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
 
-            trace!(
-                "notify_timeout::remove_from_await_list({}) {}",
-                player_id, result
-            );
-        });
+        let status = server.set_browser_rect(
+            player_id,
+            browser_id,
+            [x, y, width, height],
+            Anchor::from(anchor),
+            percent,
+        );
+
+        Ok(status as i32)
     }
 
-    fn notify_connect(&self, player_id: i32, success: bool) {
-        trace!("notify_connect({}, {})", player_id, success);
+    #[native(name = "cef_set_browser_layer")]
+    fn set_browser_layer(
+        &mut self, _: &Amx, player_id: i32, browser_id: u32, layer: i32,
+    ) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
+        let status = server.set_browser_layer(player_id, browser_id, layer);
 
-        self.amx_list.iter().for_each(|&ident| {
-            samp::amx::get(ident)
-                .map(|amx| exec_public!(amx, "OnCefInitialize", player_id, success));
-        });
+        Ok(status as i32)
     }
 
-    fn notify_browser_created(&self, player_id: i32, browser_id: u32, code: i32) {
-        self.amx_list.iter().for_each(|&ident| {
-            samp::amx::get(ident)
-                .map(|amx| exec_public!(amx, "OnCefBrowserCreated", player_id, browser_id, code));
-        });
+    #[native(name = "cef_set_zoom")]
+    fn set_zoom(
+        &mut self, _: &Amx, player_id: i32, browser_id: u32, zoom_level: f32, device_scale: f32,
+    ) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
+        let status = server.set_zoom(player_id, browser_id, zoom_level, device_scale);
+
+        Ok(status as i32)
     }
 
-    fn add_to_await_list(&mut self, player_id: i32) {
-        self.await_connect.insert(player_id, Instant::now());
+    #[native(name = "cef_set_browser_volume")]
+    fn set_browser_volume(
+        &mut self, _: &Amx, player_id: i32, browser_id: u32, volume: f32,
+    ) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
+        let status = server.set_browser_audio(player_id, browser_id, Some(volume), None);
+
+        Ok(status as i32)
     }
 
-    fn remove_from_await_list(&mut self, player_id: i32) -> bool {
-        self.await_connect.remove(&player_id).is_some()
+    #[native(name = "cef_set_browser_muted")]
+    fn set_browser_muted(
+        &mut self, _: &Amx, player_id: i32, browser_id: u32, muted: bool,
+    ) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
+        let status = server.set_browser_audio(player_id, browser_id, None, Some(muted));
+
+        Ok(status as i32)
     }
-}
 
-impl SampPlugin for CefPlugin {
-    fn on_load(&mut self) {
-        info!("CEF plugin is successful loaded.");
+    #[native(name = "cef_set_browser_max_fps")]
+    fn set_browser_max_fps(
+        &mut self, _: &Amx, player_id: i32, browser_id: u32, max_fps: i32,
+    ) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
+        let status = server.set_browser_max_fps(player_id, browser_id, max_fps);
+
+        Ok(status as i32)
     }
 
-    fn on_amx_load(&mut self, amx: &Amx) {
-        self.amx_list.push(amx.ident());
+    #[native(name = "cef_set_low_power_mode")]
+    fn set_low_power_mode(&mut self, _: &Amx, player_id: i32, enabled: bool) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
+        let status = server.set_low_power_mode(player_id, enabled);
+
+        Ok(status as i32)
     }
 
-    fn on_amx_unload(&mut self, amx: &Amx) {
-        let ident = amx.ident();
+    #[native(name = "cef_reload_browser")]
+    fn reload_browser(
+        &mut self, _: &Amx, player_id: i32, browser_id: u32, ignore_cache: bool,
+    ) -> AmxResult<i32> {
+        let action = if ignore_cache {
+            NavigationAction::RELOAD_IGNORE_CACHE
+        } else {
+            NavigationAction::RELOAD
+        };
 
-        if let Some(position) = self.amx_list.iter().position(|&id| id == ident) {
-            self.amx_list.remove(position);
-        }
+        let mut server = self.server.lock().unwrap();
+        let status = server.navigate_browser(player_id, browser_id, action);
+
+        Ok(status as i32)
     }
 
-    fn process_tick(&mut self) {
-        while let Ok(event) = self.event_rx.try_recv() {
-            match event {
-                Event::EmitEvent {
-                    player_id,
-                    event,
-                    arguments,
-                } => {
-                    trace!("process_tick::EmitEvent({}) {}", player_id, event);
+    #[native(name = "cef_go_back")]
+    fn go_back(&mut self, _: &Amx, player_id: i32, browser_id: u32) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
+        let status = server.navigate_browser(player_id, browser_id, NavigationAction::GO_BACK);
 
-                    if let Some((ident, cb)) = self.events.get(&event) {
-                        samp::amx::get(*ident)
-                            .map(|amx| exec_public!(amx, cb, player_id, &arguments => string));
-                    }
-                }
+        Ok(status as i32)
+    }
 
-                Event::PlayerConnected(player) => {
-                    trace!("process_tick::PlayerConnected({})", player);
+    #[native(name = "cef_go_forward")]
+    fn go_forward(&mut self, _: &Amx, player_id: i32, browser_id: u32) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
+        let status = server.navigate_browser(player_id, browser_id, NavigationAction::GO_FORWARD);
 
-                    if self.remove_from_await_list(player) {
-                        self.notify_connect(player, true);
-                    }
-                }
+        Ok(status as i32)
+    }
 
-                Event::BrowserCreated {
-                    player_id,
-                    browser_id,
-                    code,
-                } => {
-                    trace!("process_tick::BrowserCreated({})", player_id);
+    #[native(name = "cef_stop_loading")]
+    fn stop_loading(&mut self, _: &Amx, player_id: i32, browser_id: u32) -> AmxResult<i32> {
+        let mut server = self.server.lock().unwrap();
+        let status = server.navigate_browser(player_id, browser_id, NavigationAction::STOP);
 
-                    self.notify_browser_created(player_id, browser_id, code);
-                }
-            }
+        Ok(status as i32)
+    }
+
+    #[native(name = "cef_get_player_resolution")]
+    fn get_player_resolution(
+        &mut self, _: &Amx, player_id: i32, mut width: Ref<i32>, mut height: Ref<i32>,
+    ) -> AmxResult<bool> {
+        let server = self.server.lock().unwrap();
+
+        if let Some(info) = server.client_info(player_id) {
+            *width = info.width as i32;
+            *height = info.height as i32;
+
+            return Ok(true);
         }
 
-        self.notify_timeout();
+        Ok(false)
     }
-}
 
-initialize_plugin!(
-    natives: [
+    #[native(name = "cef_get_player_cef_version")]
+    fn get_player_cef_version(
+        &mut self, _: &Amx, player_id: i32, dest: UnsizedBuffer, size: usize,
+    ) -> AmxResult<bool> {
+        let server = self.server.lock().unwrap();
+        let version = server.client_info(player_id).map(|info| &info.cef_version);
+
+        put_string(dest, size, version)
+    }
+
+    #[native(name = "cef_get_player_chromium_version")]
+    fn get_player_chromium_version(
+        &mut self, _: &Amx, player_id: i32, dest: UnsizedBuffer, size: usize,
+    ) -> AmxResult<bool> {
+        let server = self.server.lock().unwrap();
+        let version = server
+            .client_info(player_id)
+            .map(|info| &info.chromium_version);
+
+        put_string(dest, size, version)
+    }
+
+    #[native(name = "cef_get_player_locale")]
+    fn get_player_locale(
+        &mut self, _: &Amx, player_id: i32, dest: UnsizedBuffer, size: usize,
+    ) -> AmxResult<bool> {
+        let server = self.server.lock().unwrap();
+        let locale = server.client_info(player_id).map(|info| &info.locale);
+
+        put_string(dest, size, locale)
+    }
+
+    #[native(name = "cef_get_player_render_mode")]
+    fn get_player_render_mode(&mut self, _: &Amx, player_id: i32) -> AmxResult<i32> {
+        let server = self.server.lock().unwrap();
+
+        Ok(server
+            .client_info(player_id)
+            .map(|info| info.render_mode)
+            .unwrap_or(-1))
+    }
+
+    #[native(name = "cef_player_has_client_plugin")]
+    fn player_has_client_plugin(
+        &mut self, _: &Amx, player_id: i32, name: AmxString,
+    ) -> AmxResult<bool> {
+        let name = name.to_string();
+        let server = self.server.lock().unwrap();
+
+        Ok(server
+            .client_info(player_id)
+            .map(|info| {
+                info.plugins
+                    .iter()
+                    .any(|plugin| plugin.eq_ignore_ascii_case(&name))
+            })
+            .unwrap_or(false))
+    }
+
+    #[native(name = "cef_is_packet_delivered")]
+    fn is_packet_delivered(&mut self, _: &Amx, player_id: i32, seq: u32) -> AmxResult<bool> {
+        let server = self.server.lock().unwrap();
+        Ok(server.is_packet_delivered(player_id, seq))
+    }
+
+    #[native(name = "cef_browser_exists")]
+    fn browser_exists(&mut self, _: &Amx, player_id: i32, browser_id: u32) -> AmxResult<bool> {
+        let server = self.server.lock().unwrap();
+        Ok(server.browser(player_id, browser_id).is_some())
+    }
+
+    /// клиент уже сообщил, что браузер создан
+    #[native(name = "cef_is_browser_ready")]
+    fn is_browser_ready(&mut self, _: &Amx, player_id: i32, browser_id: u32) -> AmxResult<bool> {
+        let server = self.server.lock().unwrap();
+
+        Ok(server
+            .browser(player_id, browser_id)
+            .map(|browser| browser.created)
+            .unwrap_or(false))
+    }
+
+    #[native(name = "cef_get_browser_url")]
+    fn get_browser_url(
+        &mut self, _: &Amx, player_id: i32, browser_id: u32, dest: UnsizedBuffer, size: usize,
+    ) -> AmxResult<bool> {
+        let server = self.server.lock().unwrap();
+        let url = server
+            .browser(player_id, browser_id)
+            .map(|browser| &browser.url);
+
+        put_string(dest, size, url)
+    }
+
+    #[native(name = "cef_is_browser_hidden")]
+    fn is_browser_hidden(&mut self, _: &Amx, player_id: i32, browser_id: u32) -> AmxResult<bool> {
+        let server = self.server.lock().unwrap();
+
+        Ok(server
+            .browser(player_id, browser_id)
+            .map(|browser| browser.hidden)
+            .unwrap_or(false))
+    }
+
+    #[native(name = "cef_is_browser_focused")]
+    fn is_browser_focused(&mut self, _: &Amx, player_id: i32, browser_id: u32) -> AmxResult<bool> {
+        let server = self.server.lock().unwrap();
+
+        Ok(server
+            .browser(player_id, browser_id)
+            .map(|browser| browser.focused)
+            .unwrap_or(false))
+    }
+
+    #[native(name = "cef_get_browser_texture")]
+    fn get_browser_texture(
+        &mut self, _: &Amx, player_id: i32, browser_id: u32, dest: UnsizedBuffer, size: usize,
+    ) -> AmxResult<bool> {
+        let server = self.server.lock().unwrap();
+        let texture = server
+            .browser(player_id, browser_id)
+            .and_then(|browser| browser.texture.as_ref());
+
+        put_string(dest, size, texture)
+    }
+
+    #[native(name = "cef_get_browser_objects")]
+    fn get_browser_objects(
+        &mut self, _: &Amx, player_id: i32, browser_id: u32, dest: UnsizedBuffer, size: usize,
+    ) -> AmxResult<i32> {
+        let server = self.server.lock().unwrap();
+        let objects = server
+            .browser(player_id, browser_id)
+            .map(|browser| browser.objects.iter().copied().collect())
+            .unwrap_or_default();
+
+        Ok(put_array(dest, size, objects))
+    }
+
+    #[native(name = "cef_get_player_browsers")]
+    fn get_player_browsers(
+        &mut self, _: &Amx, player_id: i32, dest: UnsizedBuffer, size: usize,
+    ) -> AmxResult<i32> {
+        let server = self.server.lock().unwrap();
+        let browsers = server
+            .browser_ids(player_id)
+            .into_iter()
+            .map(|id| id as i32)
+            .collect();
+
+        Ok(put_array(dest, size, browsers))
+    }
+
+    #[native(name = "cef_execute_js")]
+    fn execute_js(
+        &mut self, amx: &Amx, player_id: i32, browser_id: u32, code: AmxString, callback: AmxString,
+    ) -> AmxResult<i32> {
+        if !self.allow_js {
+            info!("cef_execute_js is disabled by `allow_js = false` in cef.toml");
+            return Ok(SendStatus::Dropped as i32);
+        }
+
+        let code = code.to_string();
+        let callback = callback.to_string();
+
+        let request_id = if callback.is_empty() {
+            None
+        } else {
+            self.js_request_id = self.js_request_id.wrapping_add(1);
+            self.js_callbacks
+                .insert(self.js_request_id, (player_id, amx.ident(), callback));
+
+            Some(self.js_request_id)
+        };
+
+        let mut server = self.server.lock().unwrap();
+        let status = server.execute_javascript(player_id, browser_id, code, request_id);
+
+        // ответа уже не будет
+        if status == SendStatus::Dropped
+            && let Some(request_id) = request_id
+        {
+            self.js_callbacks.remove(&request_id);
+        }
+
+        Ok(status as i32)
+    }
+
+    #[native(name = "cef_take_screenshot")]
+    fn take_screenshot(
+        &mut self, amx: &Amx, player_id: i32, browser_id: u32, callback: AmxString,
+    ) -> AmxResult<i32> {
+        let callback = callback.to_string();
+
+        let request_id = if callback.is_empty() {
+            None
+        } else {
+            self.screenshot_request_id = self.screenshot_request_id.wrapping_add(1);
+            self.screenshot_callbacks.insert(
+                self.screenshot_request_id,
+                (player_id, amx.ident(), callback),
+            );
+
+            Some(self.screenshot_request_id)
+        };
+
+        let mut server = self.server.lock().unwrap();
+        let status = server.take_screenshot(player_id, browser_id, request_id);
+
+        if status == SendStatus::Dropped
+            && let Some(request_id) = request_id
+        {
+            self.screenshot_callbacks.remove(&request_id);
+        }
+
+        Ok(status as i32)
+    }
+
+    // utils
+    /// `false`, если этот колбек скрипта уже подписан на событие
+    fn add_subscriber(&mut self, event_name: String, subscriber: Subscriber) -> bool {
+        let subscribers = self.events.entry(event_name).or_default();
+
+        if subscribers
+            .iter()
+            .any(|sub| sub.ident == subscriber.ident && sub.callback == subscriber.callback)
+        {
+            return false;
+        }
+
+        subscribers.push(subscriber);
+
+        true
+    }
+
+    fn subscribers(&self, event: &str) -> Vec<Subscriber> {
+        ordered_subscribers(&self.events, event)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    fn notify_timeout(&mut self) {
+        let mut keys = Vec::new();
+
+        for (&player_id, timing) in self.await_connect.iter() {
+            if timing.elapsed() >= self.init_timeout {
+                info!(
+                    "player {} hasn't connected to CEF server in {:?}",
+                    player_id, self.init_timeout
+                );
+
+                keys.push(player_id);
+                self.notify_connect(player_id, false);
+            }
+        }
+
+        if !keys.is_empty() {
+            let mut server = self.server.lock().unwrap();
+            keys.iter()
+                .for_each(|&player_id| server.discard_pending(player_id));
+        }
+
+        keys.into_iter().for_each(|player_id| {
+            let result = self.remove_from_await_list(player_id);
+
+            trace!(
+                "notify_timeout::remove_from_await_list({}) {}",
+                player_id, result
+            );
+        });
+    }
+
+    fn notify_connect(&self, player_id: i32, success: bool) {
+        trace!("notify_connect({}, {})", player_id, success);
+
+        self.amx_list.iter().for_each(|&ident| {
+            samp::amx::get(ident)
+                .map(|amx| exec_public!(amx, "OnCefInitialize", player_id, success));
+        });
+    }
+
+    fn notify_client_info(&self, player_id: i32) {
+        self.amx_list.iter().for_each(|&ident| {
+            samp::amx::get(ident).map(|amx| exec_public!(amx, "OnCefClientInfo", player_id));
+        });
+    }
+
+    fn notify_browser_created(&self, player_id: i32, browser_id: u32, code: i32) {
+        self.amx_list.iter().for_each(|&ident| {
+            samp::amx::get(ident)
+                .map(|amx| exec_public!(amx, "OnCefBrowserCreated", player_id, browser_id, code));
+        });
+    }
+
+    fn notify_browser_loaded(&self, player_id: i32, browser_id: u32, url: &str, code: i32) {
+        self.amx_list.iter().for_each(|&ident| {
+            samp::amx::get(ident).map(|amx| {
+                exec_public!(amx, "OnCefBrowserLoaded", player_id, browser_id, url => string, code)
+            });
+        });
+    }
+
+    fn notify_browser_load_error(
+        &self, player_id: i32, browser_id: u32, code: i32, text: &str, url: &str,
+    ) {
+        self.amx_list.iter().for_each(|&ident| {
+            samp::amx::get(ident).map(|amx| {
+                exec_public!(
+                    amx,
+                    "OnCefBrowserLoadError",
+                    player_id,
+                    browser_id,
+                    code,
+                    text => string,
+                    url => string
+                )
+            });
+        });
+    }
+
+    fn notify_browser_destroyed(&self, player_id: i32, browser_id: u32) {
+        self.amx_list.iter().for_each(|&ident| {
+            samp::amx::get(ident)
+                .map(|amx| exec_public!(amx, "OnCefBrowserDestroyed", player_id, browser_id));
+        });
+    }
+
+    fn notify_renderer_crashed(&self, player_id: i32, browser_id: u32, status: i32) {
+        self.amx_list.iter().for_each(|&ident| {
+            samp::amx::get(ident).map(|amx| {
+                exec_public!(amx, "OnCefRendererCrashed", player_id, browser_id, status)
+            });
+        });
+    }
+
+    fn notify_assets_ready(&self, player_id: i32, success: bool) {
+        self.amx_list.iter().for_each(|&ident| {
+            samp::amx::get(ident)
+                .map(|amx| exec_public!(amx, "OnCefAssetsReady", player_id, success));
+        });
+    }
+
+    fn notify_browsers_restored(&self, player_id: i32, count: u32) {
+        self.amx_list.iter().for_each(|&ident| {
+            samp::amx::get(ident)
+                .map(|amx| exec_public!(amx, "OnCefBrowsersRestored", player_id, count));
+        });
+    }
+
+    fn notify_disconnect(&self, player_id: i32, reason: DisconnectReason) {
+        // значения `CEF_DISCONNECT_*` из cef.inc
+        let reason = match reason {
+            DisconnectReason::TimedOut => 0,
+            DisconnectReason::Closed => 1,
+            DisconnectReason::Local => 2,
+            DisconnectReason::Error => 3,
+        };
+
+        self.amx_list.iter().for_each(|&ident| {
+            samp::amx::get(ident)
+                .map(|amx| exec_public!(amx, "OnCefDisconnect", player_id, reason));
+        });
+    }
+
+    fn notify_browser_focus_changed(&self, player_id: i32, browser_id: u32, focused: bool) {
+        self.amx_list.iter().for_each(|&ident| {
+            samp::amx::get(ident).map(|amx| {
+                exec_public!(
+                    amx,
+                    "OnCefBrowserFocusChanged",
+                    player_id,
+                    browser_id,
+                    focused
+                )
+            });
+        });
+    }
+
+    fn notify_browser_visibility_changed(&self, player_id: i32, browser_id: u32, hidden: bool) {
+        self.amx_list.iter().for_each(|&ident| {
+            samp::amx::get(ident).map(|amx| {
+                exec_public!(
+                    amx,
+                    "OnCefBrowserVisibilityChanged",
+                    player_id,
+                    browser_id,
+                    hidden
+                )
+            });
+        });
+    }
+
+    fn notify_event_flood(&self, player_id: i32, event: &str) {
+        self.amx_list.iter().for_each(|&ident| {
+            samp::amx::get(ident)
+                .map(|amx| exec_public!(amx, "OnCefEventFlood", player_id, event => string));
+        });
+    }
+
+    fn notify_loading_state_changed(
+        &self, player_id: i32, browser_id: u32, is_loading: bool, can_go_back: bool,
+        can_go_forward: bool,
+    ) {
+        self.amx_list.iter().for_each(|&ident| {
+            samp::amx::get(ident).map(|amx| {
+                exec_public!(
+                    amx,
+                    "OnCefBrowserLoadingState",
+                    player_id,
+                    browser_id,
+                    is_loading,
+                    can_go_back,
+                    can_go_forward
+                )
+            });
+        });
+    }
+
+    fn notify_packet_delivered(&self, player_id: i32, seq: u32) {
+        self.amx_list.iter().for_each(|&ident| {
+            samp::amx::get(ident)
+                .map(|amx| exec_public!(amx, "OnCefPacketDelivered", player_id, seq));
+        });
+    }
+
+    fn add_to_await_list(&mut self, player_id: i32) {
+        self.await_connect.insert(player_id, Instant::now());
+    }
+
+    fn remove_from_await_list(&mut self, player_id: i32) -> bool {
+        self.await_connect.remove(&player_id).is_some()
+    }
+}
+
+/// записывает строку в pawn буфер, `false` если строки нет
+fn put_string(dest: UnsizedBuffer, size: usize, value: Option<&String>) -> AmxResult<bool> {
+    let Some(value) = value else {
+        return Ok(false);
+    };
+
+    let mut dest = dest.into_sized_buffer(size);
+    samp::cell::string::put_in_buffer(&mut dest, value)?;
+
+    Ok(true)
+}
+
+/// записывает сколько влезет в pawn массив, возвращает количество записанных
+fn put_array(dest: UnsizedBuffer, size: usize, values: Vec<i32>) -> i32 {
+    let count = values.len().min(size);
+    let mut dest = dest.into_sized_buffer(size);
+
+    dest[..count].copy_from_slice(&values[..count]);
+
+    count as i32
+}
+
+/// вызывает паблик с аргументами, типы которых известны только из сигнатуры подписки
+fn exec_typed(
+    amx: &Amx, callback: &str, player_id: i32, values: &[TypedValue], browser_id: i32, event: &str,
+) -> AmxResult<i32> {
+    let index = amx.find_public(callback)?;
+    let allocator = amx.allocator();
+
+    // аргументы кладутся на стек с конца
+    let event = allocator.allot_string(event)?;
+    amx.push(event)?;
+    amx.push(browser_id)?;
+
+    for value in values.iter().rev() {
+        match value {
+            TypedValue::Integer(value) => amx.push(*value)?,
+            TypedValue::Float(value) => amx.push(*value)?,
+            TypedValue::String(value) => {
+                let value = allocator.allot_string(value)?;
+                amx.push(value)?;
+            }
+        }
+    }
+
+    amx.push(player_id)?;
+    amx.exec(index)
+}
+
+/// сначала точные подписки, затем шаблоны от самого длинного префикса к `*`
+fn ordered_subscribers<'a, T>(events: &'a HashMap<String, Vec<T>>, event: &str) -> Vec<&'a T> {
+    let mut patterns: Vec<_> = events
+        .iter()
+        .filter_map(|(name, subscribers)| {
+            let prefix = name.strip_suffix('*')?;
+            event
+                .starts_with(prefix)
+                .then_some((prefix.len(), subscribers))
+        })
+        .collect();
+
+    patterns.sort_by_key(|&(len, _)| std::cmp::Reverse(len));
+
+    // ключ со `*` - шаблон, событие `inventory:*` уже совпало с ним выше
+    events
+        .get(event)
+        .filter(|_| !event.ends_with('*'))
+        .into_iter()
+        .chain(patterns.into_iter().map(|(_, subscribers)| subscribers))
+        .flatten()
+        .collect()
+}
+
+/// вызывает подписчиков по порядку, пока кто-то не вернет `EVENT_BREAK`
+fn dispatch<T>(subscribers: impl IntoIterator<Item = T>, mut call: impl FnMut(T) -> Option<i32>) {
+    for sub in subscribers {
+        if call(sub) == Some(EVENT_BREAK) {
+            break;
+        }
+    }
+}
+
+/// собирает аргументы события из пар (тип, значение), начиная с `idx`
+fn event_arguments(args: &Args, mut idx: usize) -> Vec<EventValue<'static>> {
+    let mut arguments = Vec::with_capacity(args.count().saturating_sub(idx) / 2);
+
+    loop {
+        if idx >= args.count() {
+            break;
+        }
+
+        if let Some(ty) = args.get::<Ref<i32>>(idx) {
+            idx += 1;
+
+            let arg = match *ty {
+                0 => EventValue {
+                    string_value: Some(args.get::<AmxString>(idx).unwrap().to_string().into()),
+                    float_value: None,
+                    integer_value: None,
+                },
+
+                1 => EventValue {
+                    string_value: None,
+                    float_value: None,
+                    integer_value: Some(*args.get::<Ref<i32>>(idx).unwrap()),
+                },
+
+                2 => EventValue {
+                    string_value: None,
+                    float_value: Some(*args.get::<Ref<f32>>(idx).unwrap()),
+                    integer_value: None,
+                },
+
+                _ => break,
+            };
+
+            arguments.push(arg);
+
+            idx += 1;
+        } else {
+            break;
+        }
+    }
+
+    arguments
+}
+
+impl SampPlugin for CefPlugin {
+    fn on_load(&mut self) {
+        info!("CEF plugin is successful loaded.");
+    }
+
+    fn on_amx_load(&mut self, amx: &Amx) {
+        self.amx_list.push(amx.ident());
+    }
+
+    fn on_amx_unload(&mut self, amx: &Amx) {
+        let ident = amx.ident();
+
+        if let Some(position) = self.amx_list.iter().position(|&id| id == ident) {
+            self.amx_list.remove(position);
+        }
+
+        self.events.retain(|_, subscribers| {
+            subscribers.retain(|sub| sub.ident != ident);
+            !subscribers.is_empty()
+        });
+
+        self.js_callbacks.retain(|_, (_, id, _)| *id != ident);
+        self.screenshot_callbacks
+            .retain(|_, (_, id, _)| *id != ident);
+    }
+
+    fn process_tick(&mut self) {
+        while let Ok(event) = self.event_rx.try_recv() {
+            match event {
+                Event::EmitEvent {
+                    player_id,
+                    browser_id,
+                    event,
+                    arguments,
+                    values,
+                } => {
+                    trace!("process_tick::EmitEvent({}) {}", player_id, event);
+
+                    // старые клиенты не присылают id браузера
+                    let browser_id = browser_id.map(|id| id as i32).unwrap_or(-1);
+
+                    // колбеки могут подписываться и отписываться прямо во время вызова
+                    let subscribers = self.subscribers(&event);
+
+                    dispatch(subscribers, |sub| {
+                        let amx = samp::amx::get(sub.ident)?;
+
+                        match &sub.signature {
+                            None => exec_public!(
+                                amx,
+                                &sub.callback,
+                                player_id,
+                                &arguments => string,
+                                browser_id,
+                                &event => string
+                            ),
+
+                            Some(signature) => {
+                                let typed = match &values {
+                                    Some(values) => {
+                                        crate::signature::from_values(signature, values)
+                                    }
+                                    None => crate::signature::from_string(signature, &arguments),
+                                };
+
+                                match typed {
+                                    Ok(typed) => exec_typed(
+                                        amx,
+                                        &sub.callback,
+                                        player_id,
+                                        &typed,
+                                        browser_id,
+                                        &event,
+                                    ),
+
+                                    Err(err) => {
+                                        info!(
+                                            "{}: event {} from {} rejected: {}",
+                                            sub.callback, event, player_id, err
+                                        );
+
+                                        return None;
+                                    }
+                                }
+                            }
+                        }
+                        .ok()
+                    });
+                }
+
+                Event::PlayerConnected(player) => {
+                    trace!("process_tick::PlayerConnected({})", player);
+
+                    if self.remove_from_await_list(player) {
+                        self.notify_connect(player, true);
+                    }
+                }
+
+                Event::ClientInfo(player_id) => {
+                    trace!("process_tick::ClientInfo({})", player_id);
+
+                    self.notify_client_info(player_id);
+                }
+
+                Event::BrowserCreated {
+                    player_id,
+                    browser_id,
+                    code,
+                } => {
+                    trace!("process_tick::BrowserCreated({})", player_id);
+
+                    self.notify_browser_created(player_id, browser_id, code);
+                }
+
+                Event::BrowserLoaded {
+                    player_id,
+                    browser_id,
+                    url,
+                    code,
+                } => {
+                    trace!("process_tick::BrowserLoaded({}) {}", player_id, browser_id);
+
+                    self.notify_browser_loaded(player_id, browser_id, &url, code);
+                }
+
+                Event::BrowserLoadError {
+                    player_id,
+                    browser_id,
+                    code,
+                    text,
+                    url,
+                } => {
+                    trace!(
+                        "process_tick::BrowserLoadError({}) {}",
+                        player_id, browser_id
+                    );
+
+                    self.notify_browser_load_error(player_id, browser_id, code, &text, &url);
+                }
+
+                Event::BrowserDestroyed {
+                    player_id,
+                    browser_id,
+                } => {
+                    trace!(
+                        "process_tick::BrowserDestroyed({}) {}",
+                        player_id, browser_id
+                    );
+
+                    self.notify_browser_destroyed(player_id, browser_id);
+                }
+
+                Event::RendererCrashed {
+                    player_id,
+                    browser_id,
+                    status,
+                } => {
+                    trace!(
+                        "process_tick::RendererCrashed({}) {}",
+                        player_id, browser_id
+                    );
+
+                    self.notify_renderer_crashed(player_id, browser_id, status);
+                }
+
+                Event::LoadingStateChanged {
+                    player_id,
+                    browser_id,
+                    is_loading,
+                    can_go_back,
+                    can_go_forward,
+                } => {
+                    trace!(
+                        "process_tick::LoadingStateChanged({}) {} {}",
+                        player_id, browser_id, is_loading
+                    );
+
+                    self.notify_loading_state_changed(
+                        player_id,
+                        browser_id,
+                        is_loading,
+                        can_go_back,
+                        can_go_forward,
+                    );
+                }
+
+                Event::PacketDelivered { player_id, seq } => {
+                    trace!("process_tick::PacketDelivered({}) {}", player_id, seq);
+
+                    self.notify_packet_delivered(player_id, seq);
+                }
+
+                Event::JavaScriptResult {
+                    player_id,
+                    browser_id,
+                    request_id,
+                    success,
+                    result,
+                } => {
+                    trace!(
+                        "process_tick::JavaScriptResult({}) {}",
+                        player_id, request_id
+                    );
+
+                    if let Some((_, ident, cb)) = self.js_callbacks.remove(&request_id) {
+                        samp::amx::get(ident).map(|amx| {
+                            exec_public!(amx, &cb, player_id, browser_id, success, &result => string)
+                        });
+                    }
+                }
+
+                Event::AssetsReady { player_id, success } => {
+                    trace!("process_tick::AssetsReady({}) {}", player_id, success);
+
+                    self.notify_assets_ready(player_id, success);
+                }
+
+                Event::Screenshot {
+                    player_id,
+                    browser_id,
+                    request_id,
+                    path,
+                } => {
+                    trace!("process_tick::Screenshot({}) {}", player_id, request_id);
+
+                    if let Some((_, ident, cb)) = self.screenshot_callbacks.remove(&request_id) {
+                        let success = path.is_some();
+                        let path = path.unwrap_or_default();
+
+                        samp::amx::get(ident).map(|amx| {
+                            exec_public!(amx, &cb, player_id, browser_id, success, &path => string)
+                        });
+                    }
+                }
+
+                Event::BrowsersRestored { player_id, count } => {
+                    trace!("process_tick::BrowsersRestored({}) {}", player_id, count);
+
+                    self.notify_browsers_restored(player_id, count);
+                }
+
+                Event::EventFlood { player_id, event } => {
+                    trace!("process_tick::EventFlood({}) {}", player_id, event);
+
+                    self.notify_event_flood(player_id, &event);
+                }
+
+                Event::Disconnected { player_id, reason } => {
+                    trace!("process_tick::Disconnected({}) {:?}", player_id, reason);
+
+                    self.notify_disconnect(player_id, reason);
+                }
+
+                Event::BrowserFocusChanged {
+                    player_id,
+                    browser_id,
+                    focused,
+                } => {
+                    trace!(
+                        "process_tick::BrowserFocusChanged({}) {} {}",
+                        player_id, browser_id, focused
+                    );
+
+                    self.notify_browser_focus_changed(player_id, browser_id, focused);
+                }
+
+                Event::BrowserVisibilityChanged {
+                    player_id,
+                    browser_id,
+                    hidden,
+                } => {
+                    trace!(
+                        "process_tick::BrowserVisibilityChanged({}) {} {}",
+                        player_id, browser_id, hidden
+                    );
+
+                    self.notify_browser_visibility_changed(player_id, browser_id, hidden);
+                }
+            }
+        }
+
+        self.notify_timeout();
+
+        self.server.lock().unwrap().flush();
+    }
+}
+
+initialize_plugin!(
+    natives: [
         CefPlugin::on_player_connect,
         CefPlugin::on_player_disconnect,
         CefPlugin::create_browser,
+        CefPlugin::create_browser_tracked,
         CefPlugin::destroy_browser,
+        CefPlugin::destroy_browser_tracked,
         CefPlugin::emit_event,
+        CefPlugin::emit_event_tracked,
+        CefPlugin::emit_event_to_browser,
         CefPlugin::subscribe,
+        CefPlugin::subscribe_typed,
+        CefPlugin::unsubscribe,
+        CefPlugin::set_event_rate_limit,
         CefPlugin::block_input,
         CefPlugin::hide_browser,
+        CefPlugin::hide_browser_tracked,
         CefPlugin::browser_listen_events,
+        CefPlugin::focus_browser_tracked,
         CefPlugin::is_player_has_plugin,
         CefPlugin::create_external_browser,
         CefPlugin::append_to_object,
@@ -420,28 +1538,111 @@ initialize_plugin!(
         CefPlugin::toggle_dev_tools,
         CefPlugin::set_audio_settings,
         CefPlugin::load_url,
+        CefPlugin::load_url_tracked,
+        CefPlugin::set_browser_rect,
+        CefPlugin::set_browser_layer,
+        CefPlugin::set_zoom,
+        CefPlugin::set_browser_volume,
+        CefPlugin::set_browser_muted,
+        CefPlugin::set_browser_max_fps,
+        CefPlugin::set_low_power_mode,
+        CefPlugin::reload_browser,
+        CefPlugin::go_back,
+        CefPlugin::go_forward,
+        CefPlugin::stop_loading,
+        CefPlugin::execute_js,
+        CefPlugin::take_screenshot,
+        CefPlugin::get_player_resolution,
+        CefPlugin::get_player_cef_version,
+        CefPlugin::get_player_chromium_version,
+        CefPlugin::get_player_locale,
+        CefPlugin::get_player_render_mode,
+        CefPlugin::player_has_client_plugin,
+        CefPlugin::is_packet_delivered,
+        CefPlugin::browser_exists,
+        CefPlugin::is_browser_ready,
+        CefPlugin::get_browser_url,
+        CefPlugin::is_browser_hidden,
+        CefPlugin::is_browser_focused,
+        CefPlugin::get_browser_texture,
+        CefPlugin::get_browser_objects,
+        CefPlugin::get_player_browsers,
     ],
     {
         samp::plugin::enable_process_tick();
         samp::encoding::set_default_encoding(samp::encoding::WINDOWS_1251);
-        let _ = samp::plugin::logger(); // fuck logger
+        let (config, errors) = Config::load();
+        crate::logging::init(config.log.clone());
 
-        // let mut config = simplelog::ConfigBuilder::new();
+        for err in errors {
+            log::error!("{}", err);
+        }
 
-        // let config = config
-        //     .add_filter_allow_str("server")
-        //     .set_max_level(LevelFilter::Trace)
-        //     .build();
+        CefPlugin::new(config)
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        // CombinedLogger::init(vec![TermLogger::new(
-        //     LevelFilter::Trace,
-        //     config,
-        //     TerminalMode::Mixed,
-        //     simplelog::ColorChoice::Always,
-        // )])
-        // .unwrap();
+    fn events(names: &[&str]) -> HashMap<String, Vec<String>> {
+        names
+            .iter()
+            .map(|name| (name.to_string(), vec![name.to_string()]))
+            .collect()
+    }
 
+    fn order<'a>(events: &'a HashMap<String, Vec<String>>, event: &str) -> Vec<&'a str> {
+        ordered_subscribers(events, event)
+            .into_iter()
+            .map(String::as_str)
+            .collect()
+    }
 
-        CefPlugin::new()
+    #[test]
+    fn exact_then_longest_prefix_then_wildcard() {
+        let events = events(&[
+            "*",
+            "inventory:*",
+            "inventory:use",
+            "inventory:u*",
+            "chat:*",
+        ]);
+
+        assert_eq!(
+            order(&events, "inventory:use"),
+            ["inventory:use", "inventory:u*", "inventory:*", "*"]
+        );
+        assert_eq!(order(&events, "inventory:drop"), ["inventory:*", "*"]);
+        assert_eq!(order(&events, "other"), ["*"]);
     }
-);
+
+    #[test]
+    fn literal_pattern_event_is_dispatched_once() {
+        let events = events(&["*", "inventory:*"]);
+
+        assert_eq!(order(&events, "inventory:*"), ["inventory:*", "*"]);
+    }
+
+    #[test]
+    fn event_break_stops_dispatch() {
+        let events = events(&["*", "inventory:*", "inventory:use"]);
+        let mut called = Vec::new();
+
+        dispatch(ordered_subscribers(&events, "inventory:use"), |sub| {
+            called.push(sub.as_str());
+            (sub == "inventory:*").then_some(EVENT_BREAK)
+        });
+
+        assert_eq!(called, ["inventory:use", "inventory:*"]);
+
+        called.clear();
+        dispatch(ordered_subscribers(&events, "inventory:use"), |sub| {
+            called.push(sub.as_str());
+            Some(1)
+        });
+
+        assert_eq!(called.len(), 3);
+    }
+}
@@ -0,0 +1,392 @@
+use log::{LevelFilter, Log, Metadata, Record, warn};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+const DEFAULT_MAX_SIZE: u64 = 10 * 1024 * 1024;
+const DEFAULT_MAX_FILES: u32 = 5;
+
+/// раздел `[log]` из `cef.toml`
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    pub level: LevelFilter,
+    /// уровни отдельных модулей: `server::client = "trace"`
+    pub filters: Vec<(String, LevelFilter)>,
+    /// без файла логи пишутся только в консоль сервера
+    pub file: Option<PathBuf>,
+    /// размер файла, после которого он переименовывается в `<file>.1`. 0 - без ротации
+    pub max_size: u64,
+    /// сколько старых файлов хранить
+    pub max_files: u32,
+    /// писать в файл JSON объектами, по одному на строку
+    pub json: bool,
+}
+
+impl Default for LogConfig {
+    fn default() -> LogConfig {
+        LogConfig {
+            level: LevelFilter::Info,
+            filters: Vec::new(),
+            file: None,
+            max_size: DEFAULT_MAX_SIZE,
+            max_files: DEFAULT_MAX_FILES,
+            json: false,
+        }
+    }
+}
+
+/// ставит глобальный логгер: консоль сервера через samp и, если задан, файл
+pub fn init(config: LogConfig) {
+    let (_, console) = samp::plugin::logger().into_log();
+    let mut file_error = None;
+
+    let file = config.file.and_then(|path| {
+        LogFile::open(path.clone(), config.max_size, config.max_files)
+            .map_err(|err| file_error = Some((path, err)))
+            .ok()
+            .map(Mutex::new)
+    });
+
+    let max_level = config
+        .filters
+        .iter()
+        .map(|&(_, level)| level)
+        .fold(config.level, Ord::max);
+
+    let logger = Logger {
+        console,
+        file,
+        json: config.json,
+        level: config.level,
+        filters: config.filters,
+    };
+
+    if log::set_logger(Box::leak(Box::new(logger))).is_err() {
+        return;
+    }
+
+    log::set_max_level(max_level);
+
+    if let Some((path, err)) = file_error {
+        warn!("couldn't open log file {}: {}", path.display(), err);
+    }
+}
+
+struct Logger {
+    console: Box<dyn Log>,
+    file: Option<Mutex<LogFile>>,
+    json: bool,
+    level: LevelFilter,
+    filters: Vec<(String, LevelFilter)>,
+}
+
+impl Logger {
+    /// из подходящих фильтров главнее самый длинный
+    fn level_for(&self, target: &str) -> LevelFilter {
+        self.filters
+            .iter()
+            .filter(|(module, _)| {
+                target
+                    .strip_prefix(module.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .max_by_key(|(module, _)| module.len())
+            .map(|&(_, level)| level)
+            .unwrap_or(self.level)
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        self.console.log(record);
+
+        if let Some(file) = &self.file {
+            let line = if self.json {
+                json_line(record)
+            } else {
+                text_line(record)
+            };
+
+            if let Ok(mut file) = file.lock() {
+                file.write_line(&line);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        self.console.flush();
+
+        if let Some(Ok(mut file)) = self.file.as_ref().map(Mutex::lock) {
+            let _ = file.file.flush();
+        }
+    }
+}
+
+fn timestamp() -> String {
+    OffsetDateTime::now_utc()
+        .format(&Rfc3339)
+        .unwrap_or_default()
+}
+
+fn text_line(record: &Record) -> String {
+    format!(
+        "{} {:<5} {}: {}",
+        timestamp(),
+        record.level(),
+        record.target(),
+        record.args()
+    )
+}
+
+fn json_line(record: &Record) -> String {
+    format!(
+        r#"{{"time":"{}","level":"{}","target":"{}","message":"{}"}}"#,
+        timestamp(),
+        record.level(),
+        escape_json(record.target()),
+        escape_json(&record.args().to_string())
+    )
+}
+
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for ch in text.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            ch if ch.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", ch as u32);
+            }
+            ch => escaped.push(ch),
+        }
+    }
+
+    escaped
+}
+
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+    max_files: u32,
+}
+
+impl LogFile {
+    fn open(path: PathBuf, max_size: u64, max_files: u32) -> std::io::Result<LogFile> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+
+        Ok(LogFile {
+            path,
+            file,
+            size,
+            max_size,
+            max_files,
+        })
+    }
+
+    fn write_line(&mut self, line: &str) {
+        let len = line.len() as u64 + 1;
+
+        if self.max_size != 0 && self.size != 0 && self.size + len > self.max_size {
+            // ошибку логировать некуда, дописываем в старый файл
+            let _ = self.rotate();
+        }
+
+        if writeln!(self.file, "{}", line).is_ok() {
+            self.size += len;
+        }
+    }
+
+    /// `cef.log` -> `cef.log.1` -> `cef.log.2`, самый старый удаляется
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let mut options = OpenOptions::new();
+        options.create(true);
+
+        if self.max_files == 0 {
+            options.write(true).truncate(true);
+        } else {
+            let _ = std::fs::remove_file(self.rotated(self.max_files));
+
+            for index in (1..self.max_files).rev() {
+                let _ = std::fs::rename(self.rotated(index), self.rotated(index + 1));
+            }
+
+            std::fs::rename(&self.path, self.rotated(1))?;
+            options.append(true);
+        }
+
+        self.file = options.open(&self.path)?;
+        self.size = 0;
+
+        Ok(())
+    }
+
+    fn rotated(&self, index: u32) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", index));
+        path.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Silent;
+
+    impl Log for Silent {
+        fn enabled(&self, _: &Metadata) -> bool {
+            false
+        }
+
+        fn log(&self, _: &Record) {}
+
+        fn flush(&self) {}
+    }
+
+    fn logger(level: LevelFilter, filters: &[(&str, LevelFilter)]) -> Logger {
+        Logger {
+            console: Box::new(Silent),
+            file: None,
+            json: false,
+            level,
+            filters: filters
+                .iter()
+                .map(|&(module, level)| (module.to_owned(), level))
+                .collect(),
+        }
+    }
+
+    /// пустая папка под логи одного теста, имя с pid не пересекается с другими запусками
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cef_log_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn read(path: PathBuf) -> String {
+        std::fs::read_to_string(path).unwrap_or_default()
+    }
+
+    #[test]
+    fn escapes_quotes_and_backslashes() {
+        assert_eq!(escape_json(r#"say "hi" \o/"#), r#"say \"hi\" \\o/"#);
+    }
+
+    #[test]
+    fn escapes_control_characters() {
+        assert_eq!(escape_json("a\nb\rc\td"), r"a\nb\rc\td");
+        assert_eq!(escape_json("\u{0}\u{1b}\u{7f}"), r"\u0000\u001b\u007f");
+        assert_eq!(escape_json("привет, мир"), "привет, мир");
+    }
+
+    #[test]
+    fn longest_filter_wins() {
+        let logger = logger(
+            LevelFilter::Warn,
+            &[
+                ("server", LevelFilter::Info),
+                ("server::client", LevelFilter::Trace),
+                ("server::client::pending", LevelFilter::Off),
+            ],
+        );
+
+        assert_eq!(logger.level_for("server"), LevelFilter::Info);
+        assert_eq!(logger.level_for("server::server"), LevelFilter::Info);
+        assert_eq!(logger.level_for("server::client"), LevelFilter::Trace);
+        assert_eq!(
+            logger.level_for("server::client::other"),
+            LevelFilter::Trace
+        );
+        assert_eq!(
+            logger.level_for("server::client::pending"),
+            LevelFilter::Off
+        );
+        assert_eq!(logger.level_for("network"), LevelFilter::Warn);
+    }
+
+    #[test]
+    fn filters_match_whole_modules() {
+        let logger = logger(LevelFilter::Warn, &[("server::client", LevelFilter::Trace)]);
+
+        assert_eq!(logger.level_for("server::clients"), LevelFilter::Warn);
+        assert_eq!(logger.level_for("server"), LevelFilter::Warn);
+    }
+
+    #[test]
+    fn rotates_files() {
+        let dir = temp_dir("rotate");
+        let path = dir.join("cef.log");
+        let mut file = LogFile::open(path.clone(), 16, 2).unwrap();
+
+        for line in ["first line", "second line", "third line", "fourth line"] {
+            file.write_line(line);
+        }
+
+        assert_eq!(read(path.clone()), "fourth line\n");
+        assert_eq!(read(file.rotated(1)), "third line\n");
+        assert_eq!(read(file.rotated(2)), "second line\n");
+        assert!(!file.rotated(3).exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn truncates_without_old_files() {
+        let dir = temp_dir("truncate");
+        let path = dir.join("cef.log");
+        let mut file = LogFile::open(path.clone(), 16, 0).unwrap();
+
+        file.write_line("first line");
+        file.write_line("second line");
+
+        assert_eq!(read(path.clone()), "second line\n");
+        assert!(!file.rotated(1).exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn appends_to_existing_file() {
+        let dir = temp_dir("append");
+        let path = dir.join("cef.log");
+
+        LogFile::open(path.clone(), 0, 2)
+            .unwrap()
+            .write_line("first line");
+
+        let mut file = LogFile::open(path.clone(), 0, 2).unwrap();
+        assert_eq!(file.size, 11);
+
+        // без `max_size` файл не ротируется
+        file.write_line("second line");
+
+        assert_eq!(read(path.clone()), "first line\nsecond line\n");
+        assert!(!file.rotated(1).exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
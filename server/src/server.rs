@@ -1,17 +1,48 @@
 use crossbeam_channel::{Receiver, Sender};
-use log::trace;
-use messages::{packets, try_into_packet};
-use network::{CertStrategy, Event as SocketEvent, PeerId, Socket};
-use quick_protobuf::deserialize_from_slice;
+use log::{debug, info, trace, warn};
+use messages::{Limits, Validate, decode, packets, try_into_packet};
+use network::{CertStrategy, DisconnectReason, Event as SocketEvent, PeerId, Socket};
+use quick_protobuf::{MessageRead, MessageWrite, serialize_into_vec};
 
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::TryInto;
 use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use crate::Event;
-use crate::client::Client;
+use crate::assets::Assets;
+use crate::browser::Browser;
+use crate::client::{Client, ClientInfo, EventRate, Pending, ScreenshotChunk};
+
+/// примерный предел размера одного `Batch`, остальное уйдет следующим
+const MAX_BATCH_SIZE: usize = 1024 * 1024;
+
+/// сколько кусков файлов из `assets_dir` уходит игроку за тик
+const ASSET_CHUNKS_PER_TICK: usize = 4;
+
+/// скриншоты от клиентов сохраняются в `scriptfiles`, чтобы скрипты могли их открыть
+const SCREENSHOTS_DIR: &str = "cef_screenshots";
+
+/// что стало с пакетом, который скрипт отправил игроку
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SendStatus {
+    /// у игрока нет плагина, он вышел или очередь до входа переполнена
+    Dropped = 0,
+    Sent = 1,
+    /// уйдет, когда игрок подключится к cef серверу
+    Queued = 2,
+}
+
+/// сколько раз в секунду игрок может вызвать событие
+#[derive(Debug, Clone, Copy)]
+struct EventLimit {
+    per_second: u32,
+    /// разорвать cef соединение игрока при превышении
+    disconnect: bool,
+}
 
 enum Packet {
     Normal { peer: PeerId, bytes: Vec<u8> },
@@ -34,11 +65,42 @@ pub struct Server {
     sender: Sender<Packet>,
     allowed: HashMap<IpAddr, i32>,
     clients: HashMap<PeerId, Client>,
+    /// ключ - id игрока, пакеты ждут `REQUEST_JOIN`
+    pending: HashMap<i32, Pending>,
+    /// ключ - id игрока, браузеры, созданные сервером
+    browsers: HashMap<i32, BTreeMap<u32, Browser>>,
+    limits: Limits,
+    /// предел очереди пакетов до входа игрока
+    max_pending_size: usize,
+    assets: Arc<Assets>,
+    /// после повторного подключения клиента браузеры создаются заново
+    restore_browsers: bool,
+    /// id игроков, у которых оборвалось cef соединение, браузеры восстанавливаются только им
+    lost_sessions: HashSet<i32>,
+    /// ключ - название события, шаблон вида `inventory:*` или `*` для всех событий вместе
+    event_limits: HashMap<String, EventLimit>,
+    /// `send_packet` выдает пакетам номер только внутри `tracked`
+    track: bool,
+    tracked_seq: u32,
 }
 
 impl Server {
-    pub fn new(addr: SocketAddr) -> Arc<Mutex<Server>> {
-        let mut socket = Socket::new_server(addr, CertStrategy::SelfSigned).unwrap();
+    pub fn new(
+        addr: SocketAddr, limits: Limits, max_pending_size: usize, assets: Assets,
+        restore_browsers: bool, tls: Option<(PathBuf, PathBuf)>,
+    ) -> Arc<Mutex<Server>> {
+        let socket = match tls {
+            Some((certificate, key)) => {
+                Socket::new_server(addr, CertStrategy::Files { certificate, key }).or_else(|err| {
+                    log::error!("{:#}, fall back to a self-signed certificate", err);
+                    Socket::new_server(addr, CertStrategy::SelfSigned)
+                })
+            }
+
+            None => Socket::new_server(addr, CertStrategy::SelfSigned),
+        };
+
+        let mut socket = socket.unwrap();
 
         let (sender, receiver) = crossbeam_channel::unbounded();
         let (event_tx, event_rx) = crossbeam_channel::unbounded();
@@ -49,6 +111,16 @@ impl Server {
             sender,
             allowed: HashMap::new(),
             clients: HashMap::new(),
+            pending: HashMap::new(),
+            browsers: HashMap::new(),
+            limits,
+            max_pending_size,
+            assets: Arc::new(assets),
+            restore_browsers,
+            lost_sessions: HashSet::new(),
+            event_limits: HashMap::new(),
+            track: false,
+            tracked_seq: 0,
         };
 
         let server = Arc::new(Mutex::new(server));
@@ -60,9 +132,13 @@ impl Server {
                     match event {
                         // если послали новый пакет
                         SocketEvent::Message(peer, bytes) => {
-                            if let Ok(proto) = deserialize_from_slice::<packets::Packet>(&bytes) {
-                                let mut server = server.lock().unwrap();
-                                server.handle_client_packet(peer, proto);
+                            match decode::<packets::Packet>(&bytes, &limits) {
+                                Ok(proto) => {
+                                    let mut server = server.lock().unwrap();
+                                    server.handle_client_packet(peer, proto);
+                                }
+
+                                Err(err) => warn!("dropped a bad packet from {:?}: {}", peer, err),
                             }
                         }
 
@@ -73,9 +149,9 @@ impl Server {
                         }
 
                         // таймауты
-                        SocketEvent::Disconnect(peer, _) => {
+                        SocketEvent::Disconnect(peer, _, reason) => {
                             let mut server = server.lock().unwrap();
-                            server.handle_timeout(peer);
+                            server.handle_timeout(peer, reason);
                         }
 
                         _ => (),
@@ -105,33 +181,140 @@ impl Server {
 
         // клиента нет пшел нахрен
         if !self.clients.contains_key(&peer) {
+            debug!("dropped a packet from unknown {:?}", peer);
             return;
         }
 
         match packet.packet_id {
             PacketId::REQUEST_JOIN => {
-                let _ = deserialize_from_slice(&packet.bytes)
+                let _ = self
+                    .decode(peer, &packet.bytes)
                     .map(|packet| self.handle_auth(peer, packet));
             }
 
             PacketId::EMIT_EVENT => {
-                let _ = deserialize_from_slice(&packet.bytes)
+                let _ = self
+                    .decode(peer, &packet.bytes)
                     .map(|packet| self.handle_emit_event(peer, packet));
             }
 
             PacketId::BROWSER_CREATED => {
-                let _ = deserialize_from_slice(&packet.bytes)
+                let _ = self
+                    .decode(peer, &packet.bytes)
                     .map(|packet| self.handle_browser_created(peer, packet));
             }
 
-            _ => (),
+            PacketId::BROWSER_LOADED => {
+                let _ = self
+                    .decode(peer, &packet.bytes)
+                    .map(|packet| self.handle_browser_loaded(peer, packet));
+            }
+
+            PacketId::BROWSER_LOAD_ERROR => {
+                let _ = self
+                    .decode(peer, &packet.bytes)
+                    .map(|packet| self.handle_browser_load_error(peer, packet));
+            }
+
+            PacketId::BROWSER_DESTROYED => {
+                let _ = self
+                    .decode(peer, &packet.bytes)
+                    .map(|packet| self.handle_browser_destroyed(peer, packet));
+            }
+
+            PacketId::RENDERER_CRASHED => {
+                let _ = self
+                    .decode(peer, &packet.bytes)
+                    .map(|packet| self.handle_renderer_crashed(peer, packet));
+            }
+
+            PacketId::LOADING_STATE_CHANGED => {
+                let _ = self
+                    .decode(peer, &packet.bytes)
+                    .map(|packet| self.handle_loading_state_changed(peer, packet));
+            }
+
+            PacketId::CLIENT_INFO => {
+                let _ = self
+                    .decode(peer, &packet.bytes)
+                    .map(|packet| self.handle_client_info(peer, packet));
+            }
+
+            PacketId::GOT => {
+                let _ =
+                    decode(&packet.bytes, &self.limits).map(|packet| self.handle_got(peer, packet));
+            }
+
+            PacketId::JAVASCRIPT_RESULT => {
+                let _ = self
+                    .decode(peer, &packet.bytes)
+                    .map(|packet| self.handle_javascript_result(peer, packet));
+            }
+
+            PacketId::REQUEST_ASSETS => {
+                let _ = self
+                    .decode(peer, &packet.bytes)
+                    .map(|packet| self.handle_request_assets(peer, packet));
+            }
+
+            PacketId::ASSETS_READY => {
+                let _ = self
+                    .decode(peer, &packet.bytes)
+                    .map(|packet| self.handle_assets_ready(peer, packet));
+            }
+
+            PacketId::SCREENSHOT_CHUNK => {
+                let _ = self
+                    .decode(peer, &packet.bytes)
+                    .map(|packet| self.handle_screenshot_chunk(peer, packet));
+            }
+
+            PacketId::BROWSER_FOCUS_CHANGED => {
+                let _ = self
+                    .decode(peer, &packet.bytes)
+                    .map(|packet| self.handle_browser_focus_changed(peer, packet));
+            }
+
+            PacketId::BROWSER_VISIBILITY_CHANGED => {
+                let _ = self
+                    .decode(peer, &packet.bytes)
+                    .map(|packet| self.handle_browser_visibility_changed(peer, packet));
+            }
+
+            id => debug!("dropped a packet with unknown id {:?} from {:?}", id, peer),
         }
     }
 
+    /// пакет, который не прошел проверку, отбрасывается
+    fn decode<'a, T: MessageRead<'a> + Validate>(
+        &self, peer: PeerId, bytes: &'a [u8],
+    ) -> Option<T> {
+        decode(bytes, &self.limits)
+            .map_err(|err| {
+                let player_id = self.clients.get(&peer).map(Client::id);
+                warn!("dropped a bad packet from player {:?}: {}", player_id, err);
+            })
+            .ok()
+    }
+
     /// обработка пакета авторизации
-    fn handle_auth(&mut self, peer: PeerId, _packet: packets::RequestJoin) {
+    fn handle_auth(&mut self, peer: PeerId, packet: packets::RequestJoin) {
         let client = self.clients.get_mut(&peer).unwrap(); // safe
 
+        // иначе клиенту заново уйдут очередь, браузеры и манифест
+        if client.is_connected() {
+            warn!("player {} sent REQUEST_JOIN twice, ignored", client.id());
+            return;
+        }
+
+        client.set_version(packet.plugin_version);
+
+        info!(
+            "player {} has joined CEF server (plugin version {:#08x})",
+            client.id(),
+            packet.plugin_version
+        );
+
         let response = packets::JoinResponse {
             success: true,
             current_version: None,
@@ -139,25 +322,195 @@ impl Server {
 
         client.set_state(crate::client::State::Connected);
 
-        let _ = self.event_tx.send(Event::PlayerConnected(client.id()));
+        let player_id = client.id();
+        let _ = self.event_tx.send(Event::PlayerConnected(player_id));
 
         let _ = try_into_packet(response).map(|bytes| {
             let packet = Packet::new(peer, bytes);
             let _ = self.sender.send(packet);
         });
+
+        if !self.assets.is_empty() {
+            let assets = self.assets.clone();
+            self.send_packet(player_id, assets.manifest());
+        }
+
+        if let Some(pending) = self.pending.remove(&player_id)
+            && let Some(client) = self.clients.get_mut(&peer)
+        {
+            client.queue_pending(pending);
+        }
+
+        if self.lost_sessions.remove(&player_id) {
+            self.restore_browsers(player_id);
+        }
+    }
+
+    /// повторяет клиенту все, что он знал о браузерах до обрыва соединения
+    fn restore_browsers(&mut self, player_id: i32) {
+        let browsers = self.browsers.get(&player_id).cloned().unwrap_or_default();
+
+        for (&browser_id, browser) in &browsers {
+            let url = browser.url.as_str().into();
+
+            match &browser.texture {
+                Some(texture) => {
+                    self.send_packet(
+                        player_id,
+                        packets::CreateExternalBrowser {
+                            browser_id,
+                            url,
+                            texture: texture.as_str().into(),
+                            scale: browser.scale,
+                        },
+                    );
+
+                    if browser.hidden {
+                        self.send_packet(
+                            player_id,
+                            packets::HideBrowser {
+                                browser_id,
+                                hide: true,
+                            },
+                        );
+                    }
+
+                    if browser.focused {
+                        self.send_packet(
+                            player_id,
+                            packets::FocusBrowser {
+                                browser_id,
+                                focused: true,
+                            },
+                        );
+                    }
+                }
+
+                None => {
+                    self.send_packet(
+                        player_id,
+                        packets::CreateBrowser {
+                            browser_id,
+                            url,
+                            hidden: browser.hidden,
+                            focused: browser.focused,
+                        },
+                    );
+                }
+            }
+
+            for &object_id in &browser.objects {
+                self.send_packet(
+                    player_id,
+                    packets::AppendToObject {
+                        browser_id,
+                        object_id,
+                    },
+                );
+            }
+
+            if let Some((max_distance, reference_distance)) = browser.audio_settings {
+                self.send_packet(
+                    player_id,
+                    packets::SetAudioSettings {
+                        browser_id,
+                        max_distance,
+                        reference_distance,
+                    },
+                );
+            }
+
+            if browser.volume.is_some() || browser.muted.is_some() {
+                self.send_packet(
+                    player_id,
+                    packets::SetBrowserAudio {
+                        browser_id,
+                        volume: browser.volume,
+                        muted: browser.muted,
+                    },
+                );
+            }
+        }
+
+        let event = Event::BrowsersRestored {
+            player_id,
+            count: browsers.len() as u32,
+        };
+
+        let _ = self.event_tx.send(event);
     }
 
     fn handle_emit_event(&mut self, peer: PeerId, packet: packets::EmitEvent) {
         let client = self.clients.get_mut(&peer).unwrap(); // safe
         let player_id = client.id();
+        let name: &str = &packet.event_name;
+
+        let mut allowed = true;
+        let mut disconnect = false;
+
+        let limits = self
+            .event_limits
+            .iter()
+            .filter(|(key, _)| match key.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => *key == name,
+            });
+
+        for (key, limit) in limits {
+            match client.count_event(key, limit.per_second) {
+                EventRate::Allowed => continue,
+
+                EventRate::Flood => {
+                    disconnect |= limit.disconnect;
+
+                    let event = Event::EventFlood {
+                        player_id,
+                        event: name.to_owned(),
+                    };
+
+                    let _ = self.event_tx.send(event);
+                }
+
+                EventRate::Dropped => disconnect |= limit.disconnect,
+            }
+
+            allowed = false;
+        }
+
+        if disconnect {
+            warn!("event flood from player {}, disconnect", player_id);
+            self.kick(peer);
+            return;
+        }
+
+        if !allowed {
+            return;
+        }
 
         if let Some(args) = &packet.args {
+            let values = client.supports_typed_events().then(|| {
+                packet
+                    .arguments
+                    .iter()
+                    .map(|value| packets::EventValue {
+                        string_value: value
+                            .string_value
+                            .as_ref()
+                            .map(|value| Cow::Owned(value.to_string())),
+                        float_value: value.float_value,
+                        integer_value: value.integer_value,
+                    })
+                    .collect()
+            });
+
             let event = packet.event_name.to_string();
             let arguments = args.to_string();
             let event = Event::EmitEvent {
                 player_id,
+                browser_id: packet.browser_id,
                 arguments,
                 event,
+                values,
             };
 
             let _ = self.event_tx.send(event);
@@ -168,6 +521,10 @@ impl Server {
         let client = self.clients.get_mut(&peer).unwrap(); // safe
         let player_id = client.id();
 
+        if let Some(browser) = self.browser_mut(player_id, packet.browser_id) {
+            browser.created = true;
+        }
+
         let event = Event::BrowserCreated {
             player_id,
             browser_id: packet.browser_id,
@@ -177,10 +534,287 @@ impl Server {
         let _ = self.event_tx.send(event);
     }
 
+    fn handle_browser_loaded(&mut self, peer: PeerId, packet: packets::BrowserLoaded) {
+        let client = self.clients.get_mut(&peer).unwrap(); // safe
+        let player_id = client.id();
+
+        // после редиректов адрес может отличаться от запрошенного
+        if let Some(browser) = self.browser_mut(player_id, packet.browser_id) {
+            browser.url = packet.url.to_string();
+        }
+
+        let event = Event::BrowserLoaded {
+            player_id,
+            browser_id: packet.browser_id,
+            url: packet.url.to_string(),
+            code: packet.status_code,
+        };
+
+        let _ = self.event_tx.send(event);
+    }
+
+    fn handle_browser_load_error(&mut self, peer: PeerId, packet: packets::BrowserLoadError) {
+        let client = self.clients.get_mut(&peer).unwrap(); // safe
+        let player_id = client.id();
+
+        let event = Event::BrowserLoadError {
+            player_id,
+            browser_id: packet.browser_id,
+            code: packet.error_code,
+            text: packet.error_text.to_string(),
+            url: packet.url.to_string(),
+        };
+
+        let _ = self.event_tx.send(event);
+    }
+
+    fn handle_browser_destroyed(&mut self, peer: PeerId, packet: packets::BrowserDestroyed) {
+        let client = self.clients.get_mut(&peer).unwrap(); // safe
+        let player_id = client.id();
+
+        if let Some(browsers) = self.browsers.get_mut(&player_id) {
+            browsers.remove(&packet.browser_id);
+        }
+
+        let event = Event::BrowserDestroyed {
+            player_id,
+            browser_id: packet.browser_id,
+        };
+
+        let _ = self.event_tx.send(event);
+    }
+
+    fn handle_renderer_crashed(&mut self, peer: PeerId, packet: packets::RendererCrashed) {
+        let client = self.clients.get_mut(&peer).unwrap(); // safe
+        let player_id = client.id();
+
+        let event = Event::RendererCrashed {
+            player_id,
+            browser_id: packet.browser_id,
+            status: packet.status,
+        };
+
+        let _ = self.event_tx.send(event);
+    }
+
+    fn handle_loading_state_changed(&mut self, peer: PeerId, packet: packets::LoadingStateChanged) {
+        let client = self.clients.get_mut(&peer).unwrap(); // safe
+        let player_id = client.id();
+
+        let event = Event::LoadingStateChanged {
+            player_id,
+            browser_id: packet.browser_id,
+            is_loading: packet.is_loading,
+            can_go_back: packet.can_go_back,
+            can_go_forward: packet.can_go_forward,
+        };
+
+        let _ = self.event_tx.send(event);
+    }
+
+    fn handle_client_info(&mut self, peer: PeerId, packet: packets::ClientInfo) {
+        let client = self.clients.get_mut(&peer).unwrap(); // safe
+
+        let info = ClientInfo {
+            width: packet.width,
+            height: packet.height,
+            cef_version: packet.cef_version.to_string(),
+            chromium_version: packet.chromium_version.to_string(),
+            locale: packet.locale.to_string(),
+            render_mode: packet.render_mode as i32,
+            plugins: packet.plugins.iter().map(|name| name.to_string()).collect(),
+        };
+
+        client.set_info(info);
+
+        let _ = self.event_tx.send(Event::ClientInfo(client.id()));
+    }
+
+    /// клиент подтверждает все пакеты за тик одним `Got`
+    fn handle_got(&mut self, peer: PeerId, packet: packets::Got) {
+        let client = self.clients.get_mut(&peer).unwrap(); // safe
+        let player_id = client.id();
+
+        for seq in packet.seq.into_iter().chain(packet.seqs) {
+            if client.ack(seq) {
+                let _ = self
+                    .event_tx
+                    .send(Event::PacketDelivered { player_id, seq });
+            }
+        }
+    }
+
+    fn handle_javascript_result(&mut self, peer: PeerId, packet: packets::JavaScriptResult) {
+        let client = self.clients.get_mut(&peer).unwrap(); // safe
+        let player_id = client.id();
+
+        let event = Event::JavaScriptResult {
+            player_id,
+            browser_id: packet.browser_id,
+            request_id: packet.request_id,
+            success: packet.success,
+            result: packet.result.to_string(),
+        };
+
+        let _ = self.event_tx.send(event);
+    }
+
+    fn handle_request_assets(&mut self, peer: PeerId, packet: packets::RequestAssets) {
+        let client = self.clients.get_mut(&peer).unwrap(); // safe
+
+        for path in packet.paths {
+            if self.assets.contains(&path) && client.mark_asset_sent(&path) {
+                client.queue_asset(path.into_owned());
+                continue;
+            }
+
+            // иначе клиент будет ждать файл вечно
+            debug!(
+                "player {} requested unknown or already sent asset {}",
+                client.id(),
+                path
+            );
+
+            let failed = packets::AssetChunk {
+                path,
+                index: 0,
+                total: 0,
+                data: Cow::Borrowed(&[]),
+            };
+
+            if let Ok(packet) = packets::Packet::try_from(failed) {
+                client.queue(packets::Packet {
+                    packet_id: packet.packet_id,
+                    bytes: Cow::Owned(packet.bytes.into_owned()),
+                    seq: None,
+                });
+            }
+        }
+    }
+
+    fn handle_assets_ready(&mut self, peer: PeerId, packet: packets::AssetsReady) {
+        let client = self.clients.get_mut(&peer).unwrap(); // safe
+
+        let event = Event::AssetsReady {
+            player_id: client.id(),
+            success: packet.success,
+        };
+
+        let _ = self.event_tx.send(event);
+    }
+
+    fn handle_screenshot_chunk(&mut self, peer: PeerId, packet: packets::ScreenshotChunk) {
+        let client = self.clients.get_mut(&peer).unwrap(); // safe
+        let player_id = client.id();
+        let browser_id = packet.browser_id;
+        let request_id = packet.request_id;
+
+        let png = match client.push_screenshot_chunk(
+            request_id,
+            packet.index,
+            packet.total,
+            &packet.data,
+        ) {
+            ScreenshotChunk::Pending => return,
+            ScreenshotChunk::Complete(png) => Some(png),
+            ScreenshotChunk::Failed => None,
+        };
+
+        let event_tx = self.event_tx.clone();
+
+        // запись на диск не должна держать сервер
+        std::thread::spawn(move || {
+            let path = png.and_then(|png| {
+                let path = format!(
+                    "{}/{}_{}_{}.png",
+                    SCREENSHOTS_DIR, player_id, browser_id, request_id
+                );
+
+                let full_path = std::path::Path::new("scriptfiles").join(&path);
+
+                std::fs::create_dir_all(full_path.parent()?)
+                    .and_then(|_| std::fs::write(&full_path, png))
+                    .map_err(|err| log::error!("couldn't save screenshot {}: {}", path, err))
+                    .ok()
+                    .map(|_| path)
+            });
+
+            let event = Event::Screenshot {
+                player_id,
+                browser_id,
+                request_id,
+                path,
+            };
+
+            let _ = event_tx.send(event);
+        });
+    }
+
+    fn handle_browser_focus_changed(&mut self, peer: PeerId, packet: packets::BrowserFocusChanged) {
+        let client = self.clients.get_mut(&peer).unwrap(); // safe
+        let player_id = client.id();
+
+        if let Some(browser) = self.browser_mut(player_id, packet.browser_id) {
+            browser.focused = packet.focused;
+        }
+
+        let event = Event::BrowserFocusChanged {
+            player_id,
+            browser_id: packet.browser_id,
+            focused: packet.focused,
+        };
+
+        let _ = self.event_tx.send(event);
+    }
+
+    fn handle_browser_visibility_changed(
+        &mut self, peer: PeerId, packet: packets::BrowserVisibilityChanged,
+    ) {
+        let client = self.clients.get_mut(&peer).unwrap(); // safe
+        let player_id = client.id();
+
+        if let Some(browser) = self.browser_mut(player_id, packet.browser_id) {
+            browser.hidden = packet.hidden;
+        }
+
+        let event = Event::BrowserVisibilityChanged {
+            player_id,
+            browser_id: packet.browser_id,
+            hidden: packet.hidden,
+        };
+
+        let _ = self.event_tx.send(event);
+    }
+
     /// выпинываем игрока из списка клиентов
-    fn handle_timeout(&mut self, addr: PeerId) {
-        trace!("handle_timeout {:?}", addr);
-        self.clients.remove(&addr);
+    fn handle_timeout(&mut self, addr: PeerId, reason: DisconnectReason) {
+        trace!("handle_timeout {:?} {:?}", addr, reason);
+
+        // вместе с соединением клиент теряет и все браузеры
+        if let Some(client) = self.clients.remove(&addr) {
+            info!(
+                "CEF connection of player {} is closed: {:?}",
+                client.id(),
+                reason
+            );
+
+            self.notify_disconnect(&client, reason);
+
+            if self.restore_browsers {
+                // до входа браузеры еще лежат в очереди
+                if client.is_connected() {
+                    self.lost_sessions.insert(client.id());
+                }
+
+                self.browsers
+                    .get_mut(&client.id())
+                    .into_iter()
+                    .flat_map(|browsers| browsers.values_mut())
+                    .for_each(|browser| browser.created = false);
+            } else {
+                self.browsers.remove(&client.id());
+            }
+        }
 
         trace!("{:#?}", self.allowed);
         trace!("{:#?}", self.clients);
@@ -211,6 +845,16 @@ impl Server {
 
                 return;
             }
+
+            warn!(
+                "rejected CEF connection from {}: player {} is already connected",
+                addr, player_id
+            );
+        } else {
+            warn!(
+                "rejected CEF connection from {}: no player with this IP waits for it",
+                addr
+            );
         }
 
         let packet = Packet::disconnect(peer);
@@ -224,11 +868,19 @@ impl Server {
             self.clients.remove(&peer);
         }
 
+        self.browsers.remove(&player_id);
+        self.lost_sessions.remove(&player_id);
+
         self.allowed.insert(addr, player_id);
+        self.pending
+            .insert(player_id, Pending::new(self.max_pending_size));
     }
 
     pub fn remove_connection(&mut self, player_id: i32, addr: Option<IpAddr>) {
         let peer = self.peer_by_id(player_id);
+        self.pending.remove(&player_id);
+        self.browsers.remove(&player_id);
+        self.lost_sessions.remove(&player_id);
 
         if let Some(peer) = peer
             && let Some(client) = self.clients.remove(&peer)
@@ -244,66 +896,105 @@ impl Server {
 
     pub fn create_browser(
         &mut self, player_id: i32, browser_id: i32, url: String, hidden: bool, focused: bool,
-    ) {
-        self.send_packet(
+    ) -> SendStatus {
+        let status = self.send_packet(
             player_id,
             packets::CreateBrowser {
                 browser_id: browser_id as u32,
-                url: url.into(),
+                url: url.as_str().into(),
                 hidden,
                 focused,
             },
         );
+
+        if self.keeps_state(player_id, status) {
+            self.browsers
+                .entry(player_id)
+                .or_default()
+                .insert(browser_id as u32, Browser::new(url, hidden, focused));
+        }
+
+        status
     }
 
-    pub fn destroy_browser(&mut self, player_id: i32, browser_id: i32) {
-        self.send_packet(
+    pub fn destroy_browser(&mut self, player_id: i32, browser_id: i32) -> SendStatus {
+        let status = self.send_packet(
             player_id,
             packets::DestroyBrowser {
                 browser_id: browser_id as u32,
             },
         );
+
+        if self.keeps_state(player_id, status)
+            && let Some(browsers) = self.browsers.get_mut(&player_id)
+        {
+            browsers.remove(&(browser_id as u32));
+        }
+
+        status
     }
 
-    pub fn hide_browser(&self, player_id: i32, browser_id: i32, hide: bool) {
-        self.send_packet(
+    pub fn hide_browser(&mut self, player_id: i32, browser_id: i32, hide: bool) -> SendStatus {
+        let status = self.send_packet(
             player_id,
             packets::HideBrowser {
                 browser_id: browser_id as u32,
                 hide,
             },
         );
+
+        if self.keeps_state(player_id, status)
+            && let Some(browser) = self.browser_mut(player_id, browser_id as u32)
+        {
+            browser.hidden = hide;
+        }
+
+        status
     }
 
-    pub fn focus_browser(&self, player_id: i32, browser_id: i32, focused: bool) {
-        self.send_packet(
+    pub fn focus_browser(&mut self, player_id: i32, browser_id: i32, focused: bool) -> SendStatus {
+        let status = self.send_packet(
             player_id,
             packets::FocusBrowser {
                 browser_id: browser_id as u32,
                 focused,
             },
         );
+
+        if self.keeps_state(player_id, status)
+            && let Some(browser) = self.browser_mut(player_id, browser_id as u32)
+        {
+            browser.focused = focused;
+        }
+
+        status
     }
 
-    pub fn emit_event(&self, player_id: i32, event: &str, arguments: Vec<packets::EventValue>) {
+    pub fn emit_event(
+        &mut self, player_id: i32, browser_id: Option<u32>, event: &str,
+        arguments: Vec<packets::EventValue>,
+    ) -> SendStatus {
         self.send_packet(
             player_id,
             packets::EmitEvent {
                 event_name: event.into(),
                 args: None,
                 arguments,
+                browser_id,
             },
-        );
+        )
     }
 
-    pub fn always_listen_keys(&self, player_id: i32, browser_id: i32, listen: bool) {
+    pub fn always_listen_keys(
+        &mut self, player_id: i32, browser_id: i32, listen: bool,
+    ) -> SendStatus {
         self.send_packet(
             player_id,
             packets::AlwaysListenKeys {
                 browser_id: browser_id as u32,
                 listen,
             },
-        );
+        )
     }
 
     pub fn has_plugin(&self, player_id: i32) -> bool {
@@ -313,53 +1004,84 @@ impl Server {
     }
 
     pub fn create_external_browser(
-        &self, player_id: i32, browser_id: i32, texture: String, url: String, scale: i32,
-    ) {
-        self.send_packet(
+        &mut self, player_id: i32, browser_id: i32, texture: String, url: String, scale: i32,
+    ) -> SendStatus {
+        let status = self.send_packet(
             player_id,
             packets::CreateExternalBrowser {
                 browser_id: browser_id as u32,
-                url: url.into(),
-                texture: texture.into(),
+                url: url.as_str().into(),
+                texture: texture.as_str().into(),
                 scale,
             },
         );
+
+        if self.keeps_state(player_id, status) {
+            self.browsers
+                .entry(player_id)
+                .or_default()
+                .insert(browser_id as u32, Browser::external(url, texture, scale));
+        }
+
+        status
     }
 
-    pub fn append_to_object(&self, player_id: i32, browser_id: i32, object_id: i32) {
-        self.send_packet(
+    pub fn append_to_object(
+        &mut self, player_id: i32, browser_id: i32, object_id: i32,
+    ) -> SendStatus {
+        let status = self.send_packet(
             player_id,
             packets::AppendToObject {
                 browser_id: browser_id as u32,
                 object_id,
             },
         );
+
+        if self.keeps_state(player_id, status)
+            && let Some(browser) = self.browser_mut(player_id, browser_id as u32)
+        {
+            browser.objects.insert(object_id);
+        }
+
+        status
     }
 
-    pub fn remove_from_object(&self, player_id: i32, browser_id: i32, object_id: i32) {
-        self.send_packet(
+    pub fn remove_from_object(
+        &mut self, player_id: i32, browser_id: i32, object_id: i32,
+    ) -> SendStatus {
+        let status = self.send_packet(
             player_id,
             packets::RemoveFromObject {
                 browser_id: browser_id as u32,
                 object_id,
             },
         );
+
+        if self.keeps_state(player_id, status)
+            && let Some(browser) = self.browser_mut(player_id, browser_id as u32)
+        {
+            browser.objects.remove(&object_id);
+        }
+
+        status
     }
 
-    pub fn toggle_dev_tools(&self, player_id: i32, browser_id: i32, enabled: bool) {
+    pub fn toggle_dev_tools(
+        &mut self, player_id: i32, browser_id: i32, enabled: bool,
+    ) -> SendStatus {
         self.send_packet(
             player_id,
             packets::ToggleDevTools {
                 browser_id: browser_id as u32,
                 enabled,
             },
-        );
+        )
     }
 
     pub fn set_audio_settings(
-        &self, player_id: i32, browser_id: u32, max_distance: f32, reference_distance: f32,
-    ) {
-        self.send_packet(
+        &mut self, player_id: i32, browser_id: u32, max_distance: f32, reference_distance: f32,
+    ) -> SendStatus {
+        let status = self.send_packet(
             player_id,
             packets::SetAudioSettings {
                 browser_id,
@@ -367,16 +1089,276 @@ impl Server {
                 reference_distance,
             },
         );
+
+        if self.keeps_state(player_id, status)
+            && let Some(browser) = self.browser_mut(player_id, browser_id)
+        {
+            browser.audio_settings = Some((max_distance, reference_distance));
+        }
+
+        status
     }
 
-    pub fn load_url(&self, player_id: i32, browser_id: u32, url: String) {
-        self.send_packet(
+    pub fn load_url(&mut self, player_id: i32, browser_id: u32, url: String) -> SendStatus {
+        let status = self.send_packet(
             player_id,
             packets::LoadUrl {
                 browser_id,
-                url: url.into(),
+                url: url.as_str().into(),
             },
         );
+
+        if self.keeps_state(player_id, status)
+            && let Some(browser) = self.browser_mut(player_id, browser_id)
+        {
+            browser.url = url;
+        }
+
+        status
+    }
+
+    pub fn set_browser_rect(
+        &mut self, player_id: i32, browser_id: u32, rect: [f32; 4], anchor: packets::Anchor,
+        percent: bool,
+    ) -> SendStatus {
+        self.send_packet(
+            player_id,
+            packets::SetBrowserRect {
+                browser_id,
+                x: rect[0],
+                y: rect[1],
+                width: rect[2],
+                height: rect[3],
+                anchor,
+                percent,
+            },
+        )
+    }
+
+    pub fn set_browser_layer(&mut self, player_id: i32, browser_id: u32, layer: i32) -> SendStatus {
+        self.send_packet(player_id, packets::SetBrowserLayer { browser_id, layer })
+    }
+
+    pub fn set_zoom(
+        &mut self, player_id: i32, browser_id: u32, zoom_level: f32, device_scale: f32,
+    ) -> SendStatus {
+        let packet = packets::SetZoom {
+            browser_id,
+            zoom_level,
+            device_scale,
+        };
+
+        self.send_packet(player_id, packet)
+    }
+
+    pub fn set_browser_audio(
+        &mut self, player_id: i32, browser_id: u32, volume: Option<f32>, muted: Option<bool>,
+    ) -> SendStatus {
+        let packet = packets::SetBrowserAudio {
+            browser_id,
+            volume,
+            muted,
+        };
+
+        let status = self.send_packet(player_id, packet);
+
+        if self.keeps_state(player_id, status)
+            && let Some(browser) = self.browser_mut(player_id, browser_id)
+        {
+            browser.volume = volume.or(browser.volume);
+            browser.muted = muted.or(browser.muted);
+        }
+
+        status
+    }
+
+    pub fn set_browser_max_fps(
+        &mut self, player_id: i32, browser_id: u32, max_fps: i32,
+    ) -> SendStatus {
+        self.send_packet(
+            player_id,
+            packets::SetBrowserMaxFps {
+                browser_id,
+                max_fps,
+            },
+        )
+    }
+
+    pub fn set_low_power_mode(&mut self, player_id: i32, enabled: bool) -> SendStatus {
+        self.send_packet(player_id, packets::SetLowPowerMode { enabled })
+    }
+
+    pub fn navigate_browser(
+        &mut self, player_id: i32, browser_id: u32, action: packets::NavigationAction,
+    ) -> SendStatus {
+        self.send_packet(player_id, packets::NavigateBrowser { browser_id, action })
+    }
+
+    pub fn execute_javascript(
+        &mut self, player_id: i32, browser_id: u32, code: String, request_id: Option<u32>,
+    ) -> SendStatus {
+        self.send_packet(
+            player_id,
+            packets::ExecuteJavaScript {
+                browser_id,
+                code: code.into(),
+                request_id,
+            },
+        )
+    }
+
+    pub fn take_screenshot(
+        &mut self, player_id: i32, browser_id: u32, request_id: Option<u32>,
+    ) -> SendStatus {
+        let status = self.send_packet(
+            player_id,
+            packets::TakeScreenshot {
+                browser_id,
+                request_id,
+            },
+        );
+
+        let Some(request_id) = request_id else {
+            return status;
+        };
+
+        match status {
+            SendStatus::Sent => {
+                if let Some(client) = self
+                    .peer_by_id(player_id)
+                    .and_then(|peer| self.clients.get_mut(&peer))
+                {
+                    client.expect_screenshot(request_id);
+                }
+            }
+
+            SendStatus::Queued => {
+                if let Some(pending) = self.pending.get_mut(&player_id) {
+                    pending.expect_screenshot(request_id);
+                }
+            }
+
+            SendStatus::Dropped => (),
+        }
+
+        status
+    }
+
+    /// `per_second = 0` снимает лимит
+    pub fn set_event_rate_limit(&mut self, event: String, per_second: u32, disconnect: bool) {
+        if per_second == 0 {
+            self.event_limits.remove(&event);
+        } else {
+            let limit = EventLimit {
+                per_second,
+                disconnect,
+            };
+
+            self.event_limits.insert(event, limit);
+        }
+    }
+
+    /// игрок так и не подключился за отведенное время
+    pub fn discard_pending(&mut self, player_id: i32) {
+        if self.pending.remove(&player_id).is_some() {
+            debug!("discard pending packets of {}", player_id);
+
+            // браузеры из очереди до клиента так и не дошли
+            self.browsers.remove(&player_id);
+        }
+    }
+
+    pub fn browser(&self, player_id: i32, browser_id: u32) -> Option<&Browser> {
+        self.browsers
+            .get(&player_id)
+            .and_then(|browsers| browsers.get(&browser_id))
+    }
+
+    /// id браузеров игрока по возрастанию
+    pub fn browser_ids(&self, player_id: i32) -> Vec<u32> {
+        self.browsers
+            .get(&player_id)
+            .map(|browsers| browsers.keys().copied().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn client_info(&self, player_id: i32) -> Option<&ClientInfo> {
+        self.peer_by_id(player_id)
+            .and_then(|peer| self.clients.get(&peer))
+            .and_then(|client| client.info())
+    }
+
+    /// номер последнего пакета, отправленного `send`, или 0, если доставку не узнать
+    pub fn tracked(&mut self, send: impl FnOnce(&mut Server) -> SendStatus) -> u32 {
+        self.track = true;
+        self.tracked_seq = 0;
+
+        let status = send(self);
+        self.track = false;
+
+        if status == SendStatus::Dropped {
+            return 0;
+        }
+
+        self.tracked_seq
+    }
+
+    pub fn is_packet_delivered(&self, player_id: i32, seq: u32) -> bool {
+        self.peer_by_id(player_id)
+            .and_then(|peer| self.clients.get(&peer))
+            .map(|client| client.is_delivered(seq))
+            .unwrap_or(false)
+    }
+
+    /// отправляет всё, что накопилось за тик, одним `Batch` на каждого клиента
+    pub fn flush(&mut self) {
+        let Server {
+            clients,
+            sender,
+            assets,
+            ..
+        } = self;
+
+        for client in clients.values_mut() {
+            let peer = client.peer();
+            client.queue_asset_chunks(assets, ASSET_CHUNKS_PER_TICK);
+
+            let mut outgoing = client.take_outgoing();
+
+            if !client.supports_batch() {
+                outgoing
+                    .iter()
+                    .for_each(|packet| send_serialized(sender, peer, packet));
+
+                continue;
+            }
+
+            while !outgoing.is_empty() {
+                let mut size = 0;
+                let count = outgoing
+                    .iter()
+                    .take_while(|packet| {
+                        // длина + заголовок поля
+                        size += packet.get_size() + 8;
+                        size <= MAX_BATCH_SIZE
+                    })
+                    .count()
+                    .max(1);
+
+                if count == 1 {
+                    send_serialized(sender, peer, &outgoing.remove(0));
+                    continue;
+                }
+
+                let batch = packets::Batch {
+                    packets: outgoing.drain(..count).collect(),
+                };
+
+                let _ = try_into_packet(batch).map(|bytes| {
+                    let _ = sender.send(Packet::new(peer, bytes));
+                });
+            }
+        }
     }
 
     pub fn receiver(&self) -> Receiver<Event> {
@@ -385,21 +1367,96 @@ impl Server {
 
     // utils
 
+    /// до `REQUEST_JOIN` пакеты копятся в очереди игрока
     fn send_packet<'a, T: TryInto<packets::Packet<'a>, Error = quick_protobuf::Error>>(
-        &self, player_id: i32, packet: T,
-    ) {
-        if let Some(addr) = self.peer_by_id(player_id) {
-            let Server {
-                clients, sender, ..
-            } = self;
+        &mut self, player_id: i32, packet: T,
+    ) -> SendStatus {
+        let Ok(packet) = T::try_into(packet) else {
+            log::error!("couldn't serialize a packet for player {}", player_id);
+            return SendStatus::Dropped;
+        };
 
-            if let Some(client) = clients.get(&addr)
-                && let Ok(bytes) = try_into_packet(packet)
-            {
-                let packet = Packet::new(client.peer(), bytes);
-                let _ = sender.send(packet);
+        let mut packet = packets::Packet {
+            packet_id: packet.packet_id,
+            bytes: Cow::Owned(packet.bytes.into_owned()),
+            seq: None,
+        };
+
+        if let Some(addr) = self.peer_by_id(player_id)
+            && let Some(client) = self.clients.get_mut(&addr)
+            && client.is_connected()
+        {
+            if self.track {
+                packet.seq = client.next_seq();
+                self.tracked_seq = packet.seq.unwrap_or(0);
             }
+
+            client.queue(packet);
+
+            return SendStatus::Sent;
         }
+
+        let Some(pending) = self.pending.get_mut(&player_id) else {
+            debug!(
+                "dropped packet {:?} for player {}: no CEF connection",
+                packet.packet_id, player_id
+            );
+            return SendStatus::Dropped;
+        };
+
+        if self.track {
+            packet.seq = Some(pending.next_seq());
+        }
+
+        let seq = packet.seq.unwrap_or(0);
+
+        if !pending.push(packet) {
+            warn!(
+                "pending queue of player {} is full, packet dropped",
+                player_id
+            );
+            return SendStatus::Dropped;
+        }
+
+        if self.track {
+            self.tracked_seq = seq;
+        }
+
+        SendStatus::Queued
+    }
+
+    /// разрывает cef соединение и не дает подключиться заново до перезахода на сервер
+    fn kick(&mut self, peer: PeerId) {
+        if let Some(client) = self.clients.remove(&peer) {
+            self.notify_disconnect(&client, DisconnectReason::Local);
+            self.allowed.remove(&client.addr().ip());
+            self.browsers.remove(&client.id());
+        }
+
+        let _ = self.sender.send(Packet::disconnect(peer));
+    }
+
+    /// о разрыве сообщаем только тем, кто успел войти
+    fn notify_disconnect(&self, client: &Client, reason: DisconnectReason) {
+        if client.is_connected() {
+            let event = Event::Disconnected {
+                player_id: client.id(),
+                reason,
+            };
+
+            let _ = self.event_tx.send(event);
+        }
+    }
+
+    /// пакет дошел или игрок в разрыве, тогда изменение повторится при восстановлении
+    fn keeps_state(&self, player_id: i32, status: SendStatus) -> bool {
+        status != SendStatus::Dropped || self.lost_sessions.contains(&player_id)
+    }
+
+    fn browser_mut(&mut self, player_id: i32, browser_id: u32) -> Option<&mut Browser> {
+        self.browsers
+            .get_mut(&player_id)
+            .and_then(|browsers| browsers.get_mut(&browser_id))
     }
 
     fn peer_by_id(&self, player_id: i32) -> Option<PeerId> {
@@ -409,3 +1466,9 @@ impl Server {
             .map(|(&peer, _)| peer)
     }
 }
+
+fn send_serialized(sender: &Sender<Packet>, peer: PeerId, packet: &packets::Packet) {
+    let _ = serialize_into_vec(packet).map(|bytes| {
+        let _ = sender.send(Packet::new(peer, bytes));
+    });
+}
@@ -0,0 +1,308 @@
+use messages::packets::EventValue;
+
+use std::fmt;
+
+/// тип аргумента в сигнатуре `cef_subscribe_typed`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArgType {
+    Integer,
+    Float,
+    String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypedValue {
+    Integer(i32),
+    Float(f32),
+    String(String),
+}
+
+#[derive(Debug)]
+pub enum SignatureError {
+    Count { expected: usize, got: usize },
+    Type { index: usize, expected: ArgType },
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureError::Count { expected, got } => {
+                write!(f, "expected {} arguments, got {}", expected, got)
+            }
+
+            SignatureError::Type { index, expected } => {
+                write!(f, "argument {} is not {:?}", index, expected)
+            }
+        }
+    }
+}
+
+/// `i` или `d` - целое, `f` - дробное, `s` - строка. `None` на любой другой символ
+pub fn parse(signature: &str) -> Option<Vec<ArgType>> {
+    signature
+        .chars()
+        .map(|ch| match ch {
+            'i' | 'd' => Some(ArgType::Integer),
+            'f' => Some(ArgType::Float),
+            's' => Some(ArgType::String),
+            _ => None,
+        })
+        .collect()
+}
+
+/// типизированные аргументы от новых клиентов
+pub fn from_values(
+    signature: &[ArgType], values: &[EventValue],
+) -> Result<Vec<TypedValue>, SignatureError> {
+    check_count(signature, values.len())?;
+
+    signature
+        .iter()
+        .zip(values)
+        .enumerate()
+        .map(|(index, (&expected, value))| {
+            let typed = match expected {
+                ArgType::Integer => value.integer_value.map(TypedValue::Integer),
+                // целые числа из js приходят как integer, даже если это `5.0`
+                ArgType::Float => value
+                    .float_value
+                    .or(value.integer_value.map(|value| value as f32))
+                    .map(TypedValue::Float),
+                ArgType::String => value
+                    .string_value
+                    .as_ref()
+                    .map(|value| TypedValue::String(value.to_string())),
+            };
+
+            typed.ok_or(SignatureError::Type { index, expected })
+        })
+        .collect()
+}
+
+/// старые клиенты присылают аргументы одной строкой через пробел
+pub fn from_string(
+    signature: &[ArgType], arguments: &str,
+) -> Result<Vec<TypedValue>, SignatureError> {
+    let parts: Vec<&str> = arguments
+        .split(' ')
+        .filter(|part| !part.is_empty())
+        .collect();
+
+    check_count(signature, parts.len())?;
+
+    signature
+        .iter()
+        .zip(parts)
+        .enumerate()
+        .map(|(index, (&expected, part))| {
+            let typed = match expected {
+                ArgType::Integer => part.parse().ok().map(TypedValue::Integer),
+                ArgType::Float => part.parse().ok().map(TypedValue::Float),
+                ArgType::String => Some(TypedValue::String(part.to_owned())),
+            };
+
+            typed.ok_or(SignatureError::Type { index, expected })
+        })
+        .collect()
+}
+
+fn check_count(signature: &[ArgType], got: usize) -> Result<(), SignatureError> {
+    if signature.len() != got {
+        return Err(SignatureError::Count {
+            expected: signature.len(),
+            got,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use messages::{DecodeError, Limits, decode, packets::EmitEvent};
+
+    use std::borrow::Cow;
+
+    fn integer(value: i32) -> EventValue<'static> {
+        EventValue {
+            integer_value: Some(value),
+            ..Default::default()
+        }
+    }
+
+    fn float(value: f32) -> EventValue<'static> {
+        EventValue {
+            float_value: Some(value),
+            ..Default::default()
+        }
+    }
+
+    fn string(value: &str) -> EventValue<'_> {
+        EventValue {
+            string_value: Some(Cow::Borrowed(value)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_signatures() {
+        use ArgType::*;
+
+        assert_eq!(parse("ifs"), Some(vec![Integer, Float, String]));
+        assert_eq!(parse("d"), Some(vec![Integer]));
+        assert_eq!(parse(""), Some(vec![]));
+    }
+
+    #[test]
+    fn rejects_malformed_signatures() {
+        for signature in ["x", "isb", "i s", "I", "ii,", "ф"] {
+            assert_eq!(parse(signature), None, "{}", signature);
+        }
+    }
+
+    #[test]
+    fn rejects_wrong_arity() {
+        let signature = parse("is").unwrap();
+
+        assert!(matches!(
+            from_values(&signature, &[integer(1)]),
+            Err(SignatureError::Count {
+                expected: 2,
+                got: 1
+            })
+        ));
+
+        assert!(matches!(
+            from_values(&signature, &[integer(1), string("a"), string("b")]),
+            Err(SignatureError::Count {
+                expected: 2,
+                got: 3
+            })
+        ));
+
+        assert!(matches!(
+            from_string(&signature, "1"),
+            Err(SignatureError::Count {
+                expected: 2,
+                got: 1
+            })
+        ));
+
+        // старые клиенты делят строки по пробелам
+        assert!(matches!(
+            from_string(&signature, "1 hello world"),
+            Err(SignatureError::Count {
+                expected: 2,
+                got: 3
+            })
+        ));
+
+        assert_eq!(from_string(&[], "  ").unwrap(), []);
+    }
+
+    #[test]
+    fn converts_typed_values() {
+        let signature = parse("ifs").unwrap();
+        let values = [integer(-5), float(1.5), string("ok")];
+
+        assert_eq!(
+            from_values(&signature, &values).unwrap(),
+            [
+                TypedValue::Integer(-5),
+                TypedValue::Float(1.5),
+                TypedValue::String("ok".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn coerces_integers_and_bools() {
+        let signature = parse("fi").unwrap();
+
+        // `5.0` из js приходит целым, `true` - единицей
+        assert_eq!(
+            from_values(&signature, &[integer(5), integer(1)]).unwrap(),
+            [TypedValue::Float(5.0), TypedValue::Integer(1)]
+        );
+
+        assert_eq!(
+            from_string(&signature, "5 0").unwrap(),
+            [TypedValue::Float(5.0), TypedValue::Integer(0)]
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_types() {
+        assert!(matches!(
+            from_values(&parse("ii").unwrap(), &[integer(1), float(1.5)]),
+            Err(SignatureError::Type {
+                index: 1,
+                expected: ArgType::Integer
+            })
+        ));
+
+        assert!(matches!(
+            from_values(&parse("s").unwrap(), &[integer(1)]),
+            Err(SignatureError::Type {
+                index: 0,
+                expected: ArgType::String
+            })
+        ));
+
+        // null из js приходит пустым значением
+        assert!(matches!(
+            from_values(&parse("f").unwrap(), &[EventValue::default()]),
+            Err(SignatureError::Type {
+                index: 0,
+                expected: ArgType::Float
+            })
+        ));
+
+        assert!(matches!(
+            from_string(&parse("fi").unwrap(), "1.5 1.5"),
+            Err(SignatureError::Type {
+                index: 1,
+                expected: ArgType::Integer
+            })
+        ));
+
+        assert!(matches!(
+            from_string(&parse("i").unwrap(), "CEF_NULL"),
+            Err(SignatureError::Type { index: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn keeps_strings_up_to_the_limit() {
+        let limits = Limits {
+            max_string: 64,
+            ..Default::default()
+        };
+
+        let event = |value: &str| {
+            quick_protobuf::serialize_into_vec(&EmitEvent {
+                event_name: "event".into(),
+                args: None,
+                arguments: vec![string(value)],
+                browser_id: None,
+            })
+            .unwrap()
+        };
+
+        let long = "a".repeat(64);
+        let bytes = event(&long);
+        let decoded: EmitEvent = decode(&bytes, &limits).unwrap();
+
+        assert_eq!(
+            from_values(&parse("s").unwrap(), &decoded.arguments).unwrap(),
+            [TypedValue::String(long)]
+        );
+
+        // длинные строки отбрасываются еще при декодировании пакета
+        assert!(matches!(
+            decode::<EmitEvent>(&event(&"a".repeat(65)), &limits),
+            Err(DecodeError::LimitExceeded { .. })
+        ));
+    }
+}